@@ -1,32 +1,83 @@
+use std::{marker::PhantomData, ops::Range};
+
 use serde::{Deserialize, Serialize};
 
 mod access_col;
 mod access_row;
+mod append;
+mod append_cols;
+mod bitset;
+mod entity;
 mod extend;
 mod h_col;
+mod h_mat_mut;
 mod h_mat_ref;
+mod h_mat_view;
 mod iterator;
+mod iterator_mut;
+mod macros;
+mod num_cols;
+mod packed_row;
+mod query;
+mod remove_row;
+mod reshape;
 mod row;
 mod slicer;
+mod slicer_mut;
+mod sparse_row;
+mod split;
+mod storage;
+mod tag_row;
+mod tuple;
+mod world;
 mod writer;
 
 pub use access_col::*;
 pub use access_row::*;
+pub use append::*;
+pub use append_cols::*;
+pub use bitset::*;
+pub use entity::*;
 pub use extend::*;
 pub use h_col::*;
+pub use h_mat_mut::*;
 pub use h_mat_ref::*;
+pub use h_mat_view::*;
 pub use iterator::*;
+pub use iterator_mut::*;
+pub use num_cols::*;
+pub use packed_row::*;
+pub use query::*;
+pub use remove_row::*;
+pub use reshape::*;
 pub use row::*;
 pub use slicer::*;
+pub use slicer_mut::*;
+pub use sparse_row::*;
+pub use split::*;
+pub use storage::*;
+pub use tag_row::*;
+pub use tuple::*;
+pub use world::*;
 pub use writer::*;
 
-/// A heterogenous matrix, in which every row is a vector of a different type.
+/// A heterogenous matrix, in which every row is a vector of a different type, stored in its own `RowStorage` `S`.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct HMat<T, R> {
-    pub(crate) head_row: Row<T>,
+pub struct HMat<T, R, S = Vec<Option<T>>> {
+    pub(crate) head_row: Row<T, S>,
     pub(crate) rem: R,
 }
 
+impl<T, R: Default, S: Default> Default for HMat<T, R, S> {
+    /// Returns a `HMat` with empty rows.
+    fn default() -> Self {
+        HMat {
+            head_row: Default::default(),
+            rem: Default::default(),
+        }
+    }
+}
+
 impl<T> HMat<T, ()> {
     /// Creates a new `HMat` with a single row of `T`.
     pub fn new() -> Self {
@@ -45,9 +96,11 @@ impl<T> HMat<T, ()> {
     }
 }
 
-impl<T, R> HMat<T, R> {
+impl<T, R, S> HMat<T, R, S> {
     /// Returns a reference to the `Row<D>` of this matrix.
-    pub fn get_row_ref<D, Directive>(&self) -> &Row<D>
+    pub fn get_row_ref<D, Directive>(
+        &self,
+    ) -> &Row<D, <Self as AccessRowRef<D, Directive>>::Storage>
     where
         Self: AccessRowRef<D, Directive>,
     {
@@ -55,7 +108,9 @@ impl<T, R> HMat<T, R> {
     }
 
     /// Returns a mutable reference to the `Row<D>` of this matrix.
-    pub fn get_row_mut<D, Directive>(&mut self) -> &mut Row<D>
+    pub fn get_row_mut<D, Directive>(
+        &mut self,
+    ) -> &mut Row<D, <Self as AccessRowMut<D, Directive>>::Storage>
     where
         Self: AccessRowMut<D, Directive>,
     {
@@ -63,7 +118,10 @@ impl<T, R> HMat<T, R> {
     }
 
     /// Returns a reference to the column at the given column index `col_idx`.
-    pub fn get_col_ref<'a>(&'a self, col_idx: usize) -> HCol<&T, <Self as AccessColRef<'a, T>>::Rem>
+    pub fn get_col_ref<'a>(
+        &'a self,
+        col_idx: usize,
+    ) -> HCol<&'a T, <Self as AccessColRef<'a, T>>::Rem>
     where
         Self: AccessColRef<'a, T>,
     {
@@ -74,7 +132,7 @@ impl<T, R> HMat<T, R> {
     pub fn get_col_mut<'a>(
         &'a mut self,
         col_idx: usize,
-    ) -> HCol<&mut T, <Self as AccessColMut<'a, T>>::Rem>
+    ) -> HCol<&'a mut T, <Self as AccessColMut<'a, T>>::Rem>
     where
         Self: AccessColMut<'a, T>,
     {
@@ -97,31 +155,196 @@ impl<T, R> HMat<T, R> {
         PlaceCol::<T>::place_col(self, col_idx, col)
     }
 
+    /// Places the given column `col` at the first free column index, i.e., one without elements, or at the end, and returns the index.
+    /// Finding the free index scans the columns, while a `World` hands out the free indexes of its entities in constant time.
+    pub fn push_col(&mut self, col: HCol<T, <Self as PlaceCol<T>>::Rem>) -> usize
+    where
+        Self: PlaceCol<T> + ContainsCol + NumCols,
+    {
+        let num_cols = self.num_cols();
+        let col_idx = (0..num_cols)
+            .find(|&col_idx| !self.contains_col(col_idx))
+            .unwrap_or(num_cols);
+        self.place_col(col_idx, col);
+        col_idx
+    }
+
+    /// Removes and returns the column at the given column index `col_idx`, leaving the index free for `push_col`.
+    pub fn remove_col(&mut self, col_idx: usize) -> HCol<T, <Self as TakeCol<T>>::Rem>
+    where
+        Self: TakeCol<T>,
+    {
+        self.take_col(col_idx)
+    }
+
+    /// Moves every column of `other` to the end of this matrix, and returns the new column indexes. Every row is padded with empty elements to the new width.
+    pub fn append_cols(&mut self, other: &mut Self) -> Range<usize>
+    where
+        Self: AppendCols + NumCols,
+    {
+        let start = self.num_cols();
+        let end = start + other.num_cols();
+        self.append_cols_at(other, start);
+        self.pad_cols(end);
+        start..end
+    }
+
+    /// Removes the `Row<D>` of this matrix, returning it alongside the matrix of the remaining rows.
+    #[allow(clippy::type_complexity)]
+    pub fn remove_row<D, Directive>(
+        self,
+    ) -> (
+        Row<D, <Self as RemoveRow<D, Directive>>::Storage>,
+        <Self as RemoveRow<D, Directive>>::Rem,
+    )
+    where
+        Self: RemoveRow<D, Directive>,
+    {
+        RemoveRow::<D, Directive>::remove_row(self)
+    }
+
+    /// Moves the rows of the matrix `M`, which are any subset of the original rows in any order, out of this matrix. Returns it alongside the matrix of the remaining rows.
+    pub fn split<M, Directive>(self) -> (M, <M as Split<Self, Directive>>::Rem)
+    where
+        M: Split<Self, Directive>,
+    {
+        M::split(self)
+    }
+
+    /// Concatenates the rows of this matrix and the rows of `other`, whose row types must be disjoint from the ones of this matrix.
+    pub fn append<Other, Directive>(
+        self,
+        other: Other,
+    ) -> <Self as Append<Other, Directive>>::Output
+    where
+        Self: Append<Other, Directive>,
+    {
+        Append::<Other, Directive>::append(self, other)
+    }
+
+    /// Moves the rows of this matrix into the matrix `M`, whose rows are any subset of the original rows in any order. The remaining rows are dropped.
+    pub fn reshape<M, Directive>(self) -> M
+    where
+        M: Reshape<Self, Directive>,
+    {
+        M::reshape(self)
+    }
+
     /// Returns a *slice*, i.e., a subset of the rows, of this matrix.
-    pub fn slice<'a, D, Sr, Directive>(&'a self) -> HMatRef<'a, D, Sr>
+    pub fn slice<'a, D, Sr, Ss, Directive>(&'a self) -> HMatRef<'a, D, Sr, Ss>
     where
-        HMatRef<'a, D, Sr>: Slicer<'a, Self, D, Directive>,
+        HMatRef<'a, D, Sr, Ss>: Slicer<'a, Self, D, Directive>,
     {
         HMatRef::slice(self)
     }
 
-    /// Returns an iterator that iterates over `num_cols` many columns, with the column indexes from `0` to `num_cols-1`.
-    pub fn iter<'a>(&'a self, num_cols: usize) -> HColIter<'a, Self>
+    /// Returns an iterator that iterates over the columns matching the query `Q`, yielding them as tuples prefixed with the column index.
+    /// The fields of `Q` are either row types, which must be present, or marked as `Opt<T>`, `With<T>` or `Without<T>`.
+    pub fn query<'a, Q, Directive>(&'a self) -> QueryIter<QueryRef<'a, Self, Q, Directive>>
     where
-        Self: AccessColRef<'a, T>,
+        Self: FetchQuery<'a, Q, Directive> + NumCols,
+    {
+        QueryIter {
+            mat_ref: QueryRef {
+                mat_ref: self,
+                pd: PhantomData,
+            },
+            curr_col_idx: 0,
+            num_cols: self.num_cols(),
+        }
+    }
+
+    /// Returns a mutable *slice*, i.e., a subset of the pairwise distinct rows, of this matrix.
+    pub fn slice_mut<'a, D, Sr, Ss, Directive>(&'a mut self) -> HMatMut<'a, D, Sr, Ss>
+    where
+        HMatMut<'a, D, Sr, Ss>: SlicerMut<'a, Self, D, Directive>,
+    {
+        HMatMut::slice_mut(self)
+    }
+
+    /// Returns a *view*, i.e., a subset of the pairwise distinct rows, each of which is borrowed as marked by `Read` or `Write`.
+    pub fn view<'a, M, Vr, Directive>(&'a mut self) -> HMatView<'a, M, Vr>
+    where
+        M: AccessMode<'a>,
+        HMatView<'a, M, Vr>: SlicerMut<'a, Self, M, Directive>,
+    {
+        HMatView::slice_mut(self)
+    }
+
+    /// Returns the number of columns of this matrix, i.e., the length of its longest row.
+    pub fn num_cols(&self) -> usize
+    where
+        Self: NumCols,
+    {
+        NumCols::num_cols(self)
+    }
+
+    /// Returns an iterator that iterates over all the columns, with the column indexes from `0` to `num_cols()-1`.
+    pub fn iter<'a>(&'a self) -> HColIter<'a, Self, T>
+    where
+        Self: AccessColRef<'a, T> + NumCols,
     {
         HColIter {
             mat_ref: self,
             curr_col_idx: 0,
+            num_cols: self.num_cols(),
+            pd: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that iterates over all the columns as mutable references, with the column indexes from `0` to `num_cols()-1`.
+    pub fn iter_mut<'a>(&'a mut self) -> HColIterMut<<Self as SplitRowsMut<'a>>::Rows>
+    where
+        Self: SplitRowsMut<'a> + NumCols,
+    {
+        let num_cols = self.num_cols();
+        HColIterMut {
+            rows: self.split_rows_mut(),
+            curr_col_idx: 0,
             num_cols,
         }
     }
 
     /// Modifies the matrix with the modifications stored in the given `HMatWriter`.
-    pub fn write_with<T1, R1>(&mut self, w: HMatWriter<T1, R1>)
+    pub fn write_with<T1, R1, K: WriterKind<T1>>(&mut self, w: HMatWriter<T1, R1, K>)
+    where
+        Self: ApplyWriter<HMatWriter<T1, R1, K>, T1>,
+    {
+        ApplyWriter::<HMatWriter<T1, R1, K>, T1>::apply(self, w)
+    }
+
+    /// Returns a new writer that can be used to gather modifications and apply them at once, ordered by their kinds.
+    pub fn new_writer(&self) -> HMatWriter<T, <Self as NewWriter<T, Local>>::Rem>
+    where
+        Self: NewWriter<T, Local>,
+    {
+        self.new_writer_with_order(WriteOrder::Priority)
+    }
+
+    /// Returns a new writer that applies the modifications in the order they were queued.
+    pub fn new_writer_ordered(&self) -> HMatWriter<T, <Self as NewWriter<T, Local>>::Rem>
+    where
+        Self: NewWriter<T, Local>,
+    {
+        self.new_writer_with_order(WriteOrder::Insertion)
+    }
+
+    /// Returns a new writer that is `Send` and `Sync`, provided that the elements are.
+    pub fn new_send_writer(&self) -> HMatWriter<T, <Self as NewWriter<T, Sendable>>::Rem, Sendable>
+    where
+        Self: NewWriter<T, Sendable>,
+    {
+        self.new_writer_with_order(WriteOrder::Priority)
+    }
+
+    /// Returns a new writer that holds data-only patches instead of closures, so that it can be serialized. `P` maps each row type to its patch type, see `PatchSet`.
+    pub fn new_command_writer<P>(
+        &self,
+    ) -> HMatWriter<T, <Self as NewWriter<T, Commands<P>>>::Rem, Commands<P>>
     where
-        Self: ApplyWriter<HMatWriter<T1, R1>, T1>,
+        Self: NewWriter<T, Commands<P>>,
+        P: PatchSet<T>,
     {
-        ApplyWriter::<HMatWriter<T1, R1>, T1>::apply(self, w)
+        self.new_writer_with_order(WriteOrder::Priority)
     }
 }
@@ -1,4 +1,4 @@
-use crate::{HMat, HMatRef};
+use crate::{HMat, HMatMut, HMatRef, HMatView, Read, RowStorage, Write};
 
 use super::HCol;
 
@@ -6,14 +6,14 @@ use super::HCol;
 pub trait AccessColRef<'a, T> {
     type Rem;
     /// Returns the column with the given index `idx` as a reference.
-    fn get_col_ref(&'a self, idx: usize) -> HCol<&T, Self::Rem>;
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T, Self::Rem>;
 }
 
 /// Represents a type whose columns can be accessed as a mutable reference.
 pub trait AccessColMut<'a, T> {
     type Rem;
     /// Returns the column with the given index `idx` as a mutable reference.
-    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&mut T, Self::Rem>;
+    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&'a mut T, Self::Rem>;
 }
 
 /// Represents a type whose columns can be moved out.
@@ -29,12 +29,19 @@ pub trait PlaceCol<T> {
     fn place_col(&mut self, idx: usize, col: HCol<T, Self::Rem>);
 }
 
-impl<'a, T1, T2, R> AccessColRef<'a, T1> for HMatRef<'a, T1, HMatRef<'a, T2, R>>
+/// Represents a type that can tell whether a column has any elements.
+pub trait ContainsCol {
+    /// Returns `true` if any row has an element at the given index `idx`.
+    fn contains_col(&self, idx: usize) -> bool;
+}
+
+impl<'a, T1, T2, R, S1, S2> AccessColRef<'a, T1> for HMatRef<'a, T1, HMatRef<'a, T2, R, S2>, S1>
 where
-    HMatRef<'a, T2, R>: AccessColRef<'a, T2>,
+    S1: RowStorage<T1>,
+    HMatRef<'a, T2, R, S2>: AccessColRef<'a, T2>,
 {
-    type Rem = HCol<&'a T2, <HMatRef<'a, T2, R> as AccessColRef<'a, T2>>::Rem>;
-    fn get_col_ref(&'a self, idx: usize) -> HCol<&T1, Self::Rem> {
+    type Rem = HCol<&'a T2, <HMatRef<'a, T2, R, S2> as AccessColRef<'a, T2>>::Rem>;
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T1, Self::Rem> {
         HCol {
             elem: self.row.get(idx),
             rem: self.rem.get_col_ref(idx),
@@ -42,9 +49,12 @@ where
     }
 }
 
-impl<'a, T> AccessColRef<'a, T> for HMatRef<'a, T, ()> {
+impl<'a, T, S> AccessColRef<'a, T> for HMatRef<'a, T, (), S>
+where
+    S: RowStorage<T>,
+{
     type Rem = ();
-    fn get_col_ref(&'a self, idx: usize) -> HCol<&T, Self::Rem> {
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T, Self::Rem> {
         HCol {
             elem: self.row.get(idx),
             rem: (),
@@ -52,13 +62,14 @@ impl<'a, T> AccessColRef<'a, T> for HMatRef<'a, T, ()> {
     }
 }
 
-impl<'a, T1, T2, R> AccessColRef<'a, T1> for HMat<T1, HMat<T2, R>>
+impl<'a, T1, T2, R, S1, S2> AccessColRef<'a, T1> for HMat<T1, HMat<T2, R, S2>, S1>
 where
     Self: 'a,
-    HMat<T2, R>: AccessColRef<'a, T2>,
+    S1: RowStorage<T1>,
+    HMat<T2, R, S2>: AccessColRef<'a, T2>,
 {
-    type Rem = HCol<&'a T2, <HMat<T2, R> as AccessColRef<'a, T2>>::Rem>;
-    fn get_col_ref(&'a self, idx: usize) -> HCol<&T1, Self::Rem> {
+    type Rem = HCol<&'a T2, <HMat<T2, R, S2> as AccessColRef<'a, T2>>::Rem>;
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T1, Self::Rem> {
         HCol {
             elem: self.head_row.get(idx),
             rem: self.rem.get_col_ref(idx),
@@ -66,9 +77,12 @@ where
     }
 }
 
-impl<'a, T> AccessColRef<'a, T> for HMat<T, ()> {
+impl<'a, T, S> AccessColRef<'a, T> for HMat<T, (), S>
+where
+    S: RowStorage<T>,
+{
     type Rem = ();
-    fn get_col_ref(&'a self, idx: usize) -> HCol<&T, Self::Rem> {
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T, Self::Rem> {
         HCol {
             elem: self.head_row.get(idx),
             rem: (),
@@ -76,13 +90,14 @@ impl<'a, T> AccessColRef<'a, T> for HMat<T, ()> {
     }
 }
 
-impl<'a, T1, T2, R> AccessColMut<'a, T1> for HMat<T1, HMat<T2, R>>
+impl<'a, T1, T2, R, S1, S2> AccessColMut<'a, T1> for HMat<T1, HMat<T2, R, S2>, S1>
 where
     Self: 'a,
-    HMat<T2, R>: AccessColMut<'a, T2>,
+    S1: RowStorage<T1>,
+    HMat<T2, R, S2>: AccessColMut<'a, T2>,
 {
-    type Rem = HCol<&'a mut T2, <HMat<T2, R> as AccessColMut<'a, T2>>::Rem>;
-    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&mut T1, Self::Rem> {
+    type Rem = HCol<&'a mut T2, <HMat<T2, R, S2> as AccessColMut<'a, T2>>::Rem>;
+    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&'a mut T1, Self::Rem> {
         HCol {
             elem: self.head_row.get_mut(idx),
             rem: self.rem.get_col_mut(idx),
@@ -90,9 +105,12 @@ where
     }
 }
 
-impl<'a, T> AccessColMut<'a, T> for HMat<T, ()> {
+impl<'a, T, S> AccessColMut<'a, T> for HMat<T, (), S>
+where
+    S: RowStorage<T>,
+{
     type Rem = ();
-    fn get_col_mut(&mut self, idx: usize) -> HCol<&mut T, Self::Rem> {
+    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&'a mut T, Self::Rem> {
         HCol {
             elem: self.head_row.get_mut(idx),
             rem: (),
@@ -100,11 +118,118 @@ impl<'a, T> AccessColMut<'a, T> for HMat<T, ()> {
     }
 }
 
-impl<T1, T2, R> TakeCol<T1> for HMat<T1, HMat<T2, R>>
+/// Represents a view whose columns can be accessed as references for the read-only rows, and as mutable references for the writable rows.
+pub trait AccessColView<'a> {
+    type Col;
+    /// Returns the column with the given index `idx`.
+    fn get_col(&'a mut self, idx: usize) -> Self::Col;
+}
+
+impl<'a> AccessColView<'a> for () {
+    type Col = ();
+    fn get_col(&'a mut self, _idx: usize) -> Self::Col {}
+}
+
+impl<'a, 'b, T, S, R> AccessColView<'a> for HMatView<'b, Read<T, S>, R>
+where
+    'b: 'a,
+    T: 'b,
+    S: RowStorage<T> + 'b,
+    R: AccessColView<'a>,
+{
+    type Col = HCol<&'a T, <R as AccessColView<'a>>::Col>;
+    fn get_col(&'a mut self, idx: usize) -> Self::Col {
+        HCol {
+            elem: self.row.get(idx),
+            rem: self.rem.get_col(idx),
+        }
+    }
+}
+
+impl<'a, 'b, T, S, R> AccessColView<'a> for HMatView<'b, Write<T, S>, R>
+where
+    'b: 'a,
+    T: 'b,
+    S: RowStorage<T> + 'b,
+    R: AccessColView<'a>,
+{
+    type Col = HCol<&'a mut T, <R as AccessColView<'a>>::Col>;
+    fn get_col(&'a mut self, idx: usize) -> Self::Col {
+        HCol {
+            elem: self.row.get_mut(idx),
+            rem: self.rem.get_col(idx),
+        }
+    }
+}
+
+impl<'a, 'b, 'c, T1, T2, R, S1, S2> AccessColRef<'a, T1>
+    for HMatMut<'b, T1, HMatMut<'c, T2, R, S2>, S1>
+where
+    'b: 'a,
+    'c: 'a,
+    S1: RowStorage<T1>,
+    HMatMut<'c, T2, R, S2>: AccessColRef<'a, T2>,
+{
+    type Rem = HCol<&'a T2, <HMatMut<'c, T2, R, S2> as AccessColRef<'a, T2>>::Rem>;
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T1, Self::Rem> {
+        HCol {
+            elem: self.row.get(idx),
+            rem: self.rem.get_col_ref(idx),
+        }
+    }
+}
+
+impl<'a, 'b, T, S> AccessColRef<'a, T> for HMatMut<'b, T, (), S>
+where
+    'b: 'a,
+    S: RowStorage<T>,
+{
+    type Rem = ();
+    fn get_col_ref(&'a self, idx: usize) -> HCol<&'a T, Self::Rem> {
+        HCol {
+            elem: self.row.get(idx),
+            rem: (),
+        }
+    }
+}
+
+impl<'a, 'b, 'c, T1, T2, R, S1, S2> AccessColMut<'a, T1>
+    for HMatMut<'b, T1, HMatMut<'c, T2, R, S2>, S1>
+where
+    'b: 'a,
+    'c: 'a,
+    S1: RowStorage<T1>,
+    HMatMut<'c, T2, R, S2>: AccessColMut<'a, T2>,
+{
+    type Rem = HCol<&'a mut T2, <HMatMut<'c, T2, R, S2> as AccessColMut<'a, T2>>::Rem>;
+    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&'a mut T1, Self::Rem> {
+        HCol {
+            elem: self.row.get_mut(idx),
+            rem: self.rem.get_col_mut(idx),
+        }
+    }
+}
+
+impl<'a, 'b, T, S> AccessColMut<'a, T> for HMatMut<'b, T, (), S>
 where
-    HMat<T2, R>: TakeCol<T2>,
+    'b: 'a,
+    S: RowStorage<T>,
 {
-    type Rem = HCol<T2, <HMat<T2, R> as TakeCol<T2>>::Rem>;
+    type Rem = ();
+    fn get_col_mut(&'a mut self, idx: usize) -> HCol<&'a mut T, Self::Rem> {
+        HCol {
+            elem: self.row.get_mut(idx),
+            rem: (),
+        }
+    }
+}
+
+impl<T1, T2, R, S1, S2> TakeCol<T1> for HMat<T1, HMat<T2, R, S2>, S1>
+where
+    S1: RowStorage<T1>,
+    HMat<T2, R, S2>: TakeCol<T2>,
+{
+    type Rem = HCol<T2, <HMat<T2, R, S2> as TakeCol<T2>>::Rem>;
     fn take_col(&mut self, idx: usize) -> HCol<T1, Self::Rem> {
         HCol {
             elem: self.head_row.take(idx),
@@ -112,7 +237,10 @@ where
         }
     }
 }
-impl<T> TakeCol<T> for HMat<T, ()> {
+impl<T, S> TakeCol<T> for HMat<T, (), S>
+where
+    S: RowStorage<T>,
+{
     type Rem = ();
     fn take_col(&mut self, idx: usize) -> HCol<T, Self::Rem> {
         HCol {
@@ -122,11 +250,12 @@ impl<T> TakeCol<T> for HMat<T, ()> {
     }
 }
 
-impl<T1, T2, R> PlaceCol<T1> for HMat<T1, HMat<T2, R>>
+impl<T1, T2, R, S1, S2> PlaceCol<T1> for HMat<T1, HMat<T2, R, S2>, S1>
 where
-    HMat<T2, R>: PlaceCol<T2>,
+    S1: RowStorage<T1>,
+    HMat<T2, R, S2>: PlaceCol<T2>,
 {
-    type Rem = HCol<T2, <HMat<T2, R> as PlaceCol<T2>>::Rem>;
+    type Rem = HCol<T2, <HMat<T2, R, S2> as PlaceCol<T2>>::Rem>;
 
     fn place_col(&mut self, idx: usize, col: HCol<T1, Self::Rem>) {
         if let Some(elem) = col.elem {
@@ -136,7 +265,10 @@ where
     }
 }
 
-impl<T> PlaceCol<T> for HMat<T, ()> {
+impl<T, S> PlaceCol<T> for HMat<T, (), S>
+where
+    S: RowStorage<T>,
+{
     type Rem = ();
 
     fn place_col(&mut self, idx: usize, col: HCol<T, Self::Rem>) {
@@ -146,6 +278,22 @@ impl<T> PlaceCol<T> for HMat<T, ()> {
     }
 }
 
+impl ContainsCol for () {
+    fn contains_col(&self, _idx: usize) -> bool {
+        false
+    }
+}
+
+impl<T, R, S> ContainsCol for HMat<T, R, S>
+where
+    S: RowStorage<T>,
+    R: ContainsCol,
+{
+    fn contains_col(&self, idx: usize) -> bool {
+        self.head_row.contains(idx) || self.rem.contains_col(idx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -175,6 +323,7 @@ mod tests {
         {
             // ... or as a mutable reference...
             let col_mut: HCol<&mut i32, HCol<&mut f32, HCol<&mut usize, ()>>> = mat.get_col_mut(0);
+            #[allow(clippy::option_map_unit_fn)]
             col_mut.elem.map(|v| *v += 1);
         }
         {
@@ -227,4 +376,20 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn push_remove_col() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), None, Some(2)])
+            .extend_with::<f32, _>([None, None, Some(2.5)]);
+        // The first column without elements is reused, and the columns are appended otherwise.
+        assert_eq!(mat.push_col(HCol::new(Some(1.5), HCol::new(None, ()))), 1);
+        assert_eq!(mat.push_col(HCol::new(None, HCol::new(Some(3), ()))), 3);
+        let col = mat.remove_col(0);
+        assert_eq!(col.into_tuple(), (None, Some(0)));
+        assert_eq!(mat.push_col(HCol::new(Some(4.5), HCol::new(None, ()))), 0);
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(4.5), Some(1.5), Some(2.5)])
+        );
+    }
 }
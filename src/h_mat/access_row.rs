@@ -1,4 +1,4 @@
-use crate::{HMat, HMatRef};
+use crate::{AccessMode, HMat, HMatMut, HMatRef, HMatView, RowStorage, Write};
 
 use super::Row;
 
@@ -8,59 +8,162 @@ pub struct AccessRowDirective<T>(T);
 
 /// Represents a type whose rows can be accessed as a reference.
 pub trait AccessRowRef<D, Directive> {
+    /// The storage of the `Row<D>`.
+    type Storage: RowStorage<D>;
     /// Returns a reference to the `Row<D>`.
-    fn get_row_ref(&self) -> &Row<D>;
+    fn get_row_ref(&self) -> &Row<D, Self::Storage>;
 }
 
 /// Represents a type whose rows can be accessed as a mutable reference.
 pub trait AccessRowMut<D, Directive> {
+    /// The storage of the `Row<D>`.
+    type Storage: RowStorage<D>;
     /// Returns a mutable reference to the `Row<D>`.
-    fn get_row_mut(&mut self) -> &mut Row<D>;
+    fn get_row_mut(&mut self) -> &mut Row<D, Self::Storage>;
 }
 
-impl<'a, D, R> AccessRowRef<D, ()> for HMatRef<'a, D, R> {
-    fn get_row_ref(&self) -> &Row<D> {
-        &self.row
+impl<'a, D, R, S> AccessRowRef<D, ()> for HMatRef<'a, D, R, S>
+where
+    S: RowStorage<D>,
+{
+    type Storage = S;
+    fn get_row_ref(&self) -> &Row<D, S> {
+        self.row
+    }
+}
+
+impl<'a, T, R, S, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMatRef<'a, T, R, S>
+where
+    R: AccessRowRef<D, A>,
+{
+    type Storage = <R as AccessRowRef<D, A>>::Storage;
+    fn get_row_ref(&self) -> &Row<D, Self::Storage> {
+        self.rem.get_row_ref()
+    }
+}
+
+// Implementation of AccessRowRef and AccessRowMut for HMatMut
+impl<'a, D, R, S> AccessRowRef<D, ()> for HMatMut<'a, D, R, S>
+where
+    S: RowStorage<D>,
+{
+    type Storage = S;
+    fn get_row_ref(&self) -> &Row<D, S> {
+        self.row
+    }
+}
+
+impl<'a, T, R, S, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMatMut<'a, T, R, S>
+where
+    R: AccessRowRef<D, A>,
+{
+    type Storage = <R as AccessRowRef<D, A>>::Storage;
+    fn get_row_ref(&self) -> &Row<D, Self::Storage> {
+        self.rem.get_row_ref()
+    }
+}
+
+impl<'a, D, R, S> AccessRowMut<D, ()> for HMatMut<'a, D, R, S>
+where
+    S: RowStorage<D>,
+{
+    type Storage = S;
+    fn get_row_mut(&mut self) -> &mut Row<D, S> {
+        self.row
+    }
+}
+
+impl<'a, T, R, S, D, A> AccessRowMut<D, AccessRowDirective<A>> for HMatMut<'a, T, R, S>
+where
+    R: AccessRowMut<D, A>,
+{
+    type Storage = <R as AccessRowMut<D, A>>::Storage;
+    fn get_row_mut(&mut self) -> &mut Row<D, Self::Storage> {
+        self.rem.get_row_mut()
+    }
+}
+
+// Implementation of AccessRowRef and AccessRowMut for HMatView
+impl<'a, D, M, R> AccessRowRef<D, ()> for HMatView<'a, M, R>
+where
+    M: AccessMode<'a, Elem = D>,
+{
+    type Storage = M::Storage;
+    fn get_row_ref(&self) -> &Row<D, M::Storage> {
+        M::row_ref(&self.row)
     }
 }
 
-impl<'a, T, R, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMatRef<'a, T, R>
+impl<'a, M, R, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMatView<'a, M, R>
 where
+    M: AccessMode<'a>,
     R: AccessRowRef<D, A>,
 {
-    fn get_row_ref(&self) -> &Row<D> {
+    type Storage = <R as AccessRowRef<D, A>>::Storage;
+    fn get_row_ref(&self) -> &Row<D, Self::Storage> {
         self.rem.get_row_ref()
     }
 }
 
+impl<'a, D: 'a, S, R> AccessRowMut<D, ()> for HMatView<'a, Write<D, S>, R>
+where
+    S: RowStorage<D> + 'a,
+{
+    type Storage = S;
+    fn get_row_mut(&mut self) -> &mut Row<D, S> {
+        self.row
+    }
+}
+
+impl<'a, M, R, D, A> AccessRowMut<D, AccessRowDirective<A>> for HMatView<'a, M, R>
+where
+    M: AccessMode<'a>,
+    R: AccessRowMut<D, A>,
+{
+    type Storage = <R as AccessRowMut<D, A>>::Storage;
+    fn get_row_mut(&mut self) -> &mut Row<D, Self::Storage> {
+        self.rem.get_row_mut()
+    }
+}
+
 // Implementation of AccessRowRef for HMat
-impl<D, R> AccessRowRef<D, ()> for HMat<D, R> {
-    fn get_row_ref(&self) -> &Row<D> {
+impl<D, R, S> AccessRowRef<D, ()> for HMat<D, R, S>
+where
+    S: RowStorage<D>,
+{
+    type Storage = S;
+    fn get_row_ref(&self) -> &Row<D, S> {
         &self.head_row
     }
 }
 
-impl<T, R, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMat<T, R>
+impl<T, R, S, D, A> AccessRowRef<D, AccessRowDirective<A>> for HMat<T, R, S>
 where
     R: AccessRowRef<D, A>,
 {
-    fn get_row_ref(&self) -> &Row<D> {
+    type Storage = <R as AccessRowRef<D, A>>::Storage;
+    fn get_row_ref(&self) -> &Row<D, Self::Storage> {
         self.rem.get_row_ref()
     }
 }
 
 // Implementation of AccessRowMut for HMat
-impl<D, R> AccessRowMut<D, ()> for HMat<D, R> {
-    fn get_row_mut(&mut self) -> &mut Row<D> {
+impl<D, R, S> AccessRowMut<D, ()> for HMat<D, R, S>
+where
+    S: RowStorage<D>,
+{
+    type Storage = S;
+    fn get_row_mut(&mut self) -> &mut Row<D, S> {
         &mut self.head_row
     }
 }
 
-impl<T, R, D, A> AccessRowMut<D, AccessRowDirective<A>> for HMat<T, R>
+impl<T, R, S, D, A> AccessRowMut<D, AccessRowDirective<A>> for HMat<T, R, S>
 where
     R: AccessRowMut<D, A>,
 {
-    fn get_row_mut(&mut self) -> &mut Row<D> {
+    type Storage = <R as AccessRowMut<D, A>>::Storage;
+    fn get_row_mut(&mut self) -> &mut Row<D, Self::Storage> {
         self.rem.get_row_mut()
     }
 }
@@ -88,7 +191,9 @@ mod tests {
                 i32_row_mut,
                 &Row::<i32>::from_iter([Some(1), None, Some(3)])
             );
-            i32_row_mut.get_mut(2).map(|val| *val += 1);
+            if let Some(val) = i32_row_mut.get_mut(2) {
+                *val += 1;
+            }
             assert_eq!(
                 i32_row_mut,
                 &Row::<i32>::from_iter([Some(1), None, Some(4)])
@@ -0,0 +1,136 @@
+use crate::{AccessRowRef, HMat, HMatRef, SlicerDirective};
+
+/// Represents a type whose row types are pairwise distinct.
+///
+/// Like the mutable slices, the proof relies on every row being found by exactly one `AccessRowDirective`: the directive
+/// that finds a row type is inferred, so a row type that occurs twice leaves it ambiguous and fails to compile.
+pub trait DistinctRows<Directive> {}
+
+impl DistinctRows<()> for () {}
+
+impl<T, R, S, A, Tail> DistinctRows<SlicerDirective<A, Tail>> for HMat<T, R, S>
+where
+    Self: AccessRowRef<T, A>,
+    R: DistinctRows<Tail>,
+{
+}
+
+impl<'a, T, R, S, A, Tail> DistinctRows<SlicerDirective<A, Tail>> for HMatRef<'a, T, R, S>
+where
+    Self: AccessRowRef<T, A>,
+    R: DistinctRows<Tail>,
+{
+}
+
+/// Represents a type that can be concatenated with another type `Other` of the same kind, regardless of their row types.
+pub trait Concat<Other> {
+    type Output;
+    /// Returns the concatenation of the rows of this type and the rows of `other`. The columns stay aligned by their indexes.
+    fn concat(self, other: Other) -> Self::Output;
+}
+
+/// Represents a type that can be concatenated with another type `Other` of the same kind, whose row types are disjoint from its own.
+///
+/// Appending a matrix that shares a row type fails to compile:
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// let physics = HMat::<f32, ()>::new().extend::<usize>();
+/// let render = HMat::<char, ()>::new().extend::<usize>();
+/// let _ = physics.append(render);
+/// ```
+pub trait Append<Other, Directive> {
+    type Output;
+    /// Returns the concatenation of the rows of this type and the rows of `other`. The columns stay aligned by their indexes.
+    fn append(self, other: Other) -> Self::Output;
+}
+
+impl<H, Other, Directive> Append<Other, Directive> for H
+where
+    H: Concat<Other>,
+    H::Output: DistinctRows<Directive>,
+{
+    type Output = H::Output;
+    fn append(self, other: Other) -> Self::Output {
+        self.concat(other)
+    }
+}
+
+impl<T, S, U, R2, S2> Concat<HMat<U, R2, S2>> for HMat<T, (), S> {
+    type Output = HMat<T, HMat<U, R2, S2>, S>;
+    fn concat(self, other: HMat<U, R2, S2>) -> Self::Output {
+        HMat {
+            head_row: self.head_row,
+            rem: other,
+        }
+    }
+}
+
+impl<T, T2, R, S, S2, Other> Concat<Other> for HMat<T, HMat<T2, R, S2>, S>
+where
+    HMat<T2, R, S2>: Concat<Other>,
+{
+    type Output = HMat<T, <HMat<T2, R, S2> as Concat<Other>>::Output, S>;
+    fn concat(self, other: Other) -> Self::Output {
+        HMat {
+            head_row: self.head_row,
+            rem: self.rem.concat(other),
+        }
+    }
+}
+
+impl<'a, T, S, U, R2, S2> Concat<HMatRef<'a, U, R2, S2>> for HMatRef<'a, T, (), S> {
+    type Output = HMatRef<'a, T, HMatRef<'a, U, R2, S2>, S>;
+    fn concat(self, other: HMatRef<'a, U, R2, S2>) -> Self::Output {
+        HMatRef {
+            row: self.row,
+            rem: other,
+        }
+    }
+}
+
+impl<'a, T, T2, R, S, S2, Other> Concat<Other> for HMatRef<'a, T, HMatRef<'a, T2, R, S2>, S>
+where
+    HMatRef<'a, T2, R, S2>: Concat<Other>,
+{
+    type Output = HMatRef<'a, T, <HMatRef<'a, T2, R, S2> as Concat<Other>>::Output, S>;
+    fn concat(self, other: Other) -> Self::Output {
+        HMatRef {
+            row: self.row,
+            rem: self.rem.concat(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn append() {
+        let physics = HMat::<f32, ()>::new_with([Some(0.5), None]).extend::<usize>();
+        let render =
+            HMat::<char, ()>::new_with([None, Some('b')]).extend_with::<i32, _>([Some(-1)]);
+        // Concatenate the rows of both matrices.
+        let world = physics.append(render);
+        let col = world.get_col_ref(0);
+        assert_eq!(
+            (
+                col.elem,
+                col.rem.elem,
+                col.rem.rem.elem,
+                col.rem.rem.rem.elem
+            ),
+            (None, Some(&0.5), Some(&-1), None)
+        );
+        assert_eq!(world.get_col_ref(1).get::<&char, _>(), Some(&&'b'));
+        // Slices can be joined as well.
+        let left: HMatRef<char, ()> = world.slice();
+        let right: HMatRef<i32, HMatRef<f32, ()>> = world.slice();
+        let joined: HMatRef<char, HMatRef<i32, HMatRef<f32, ()>>> = left.append(right);
+        let items: Vec<_> = joined
+            .query::<HQuery<Opt<char>, HQuery<f32, ()>>, _>()
+            .collect();
+        assert_eq!(items, vec![(0, None, &0.5)]);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{HMat, RowStorage};
+
+/// Represents a type whose columns can be moved to the end of another instance of the same type.
+pub trait AppendCols {
+    /// Moves the elements of every row of `other` to the same row of this type, shifting their column indexes by `offset`.
+    /// Every row is grown to at least `offset` plus the length of the same row of `other`, so the trailing empty columns are kept. The rows of `other` are left empty.
+    fn append_cols_at(&mut self, other: &mut Self, offset: usize);
+    /// Grows every row that is shorter than `len` to the length `len` with empty elements.
+    fn pad_cols(&mut self, len: usize);
+}
+
+impl AppendCols for () {
+    fn append_cols_at(&mut self, _other: &mut Self, _offset: usize) {}
+
+    fn pad_cols(&mut self, _len: usize) {}
+}
+
+impl<T, R, S> AppendCols for HMat<T, R, S>
+where
+    S: RowStorage<T>,
+    R: AppendCols,
+{
+    fn append_cols_at(&mut self, other: &mut Self, offset: usize) {
+        let mut row = std::mem::take(&mut other.head_row);
+        let col_idxs: Vec<_> = row.iter().map(|(col_idx, _)| col_idx).collect();
+        for col_idx in col_idxs {
+            if let Some(elem) = row.take(col_idx) {
+                self.head_row.place(offset + col_idx, elem);
+            }
+        }
+        self.pad_row(offset + row.len());
+        self.rem.append_cols_at(&mut other.rem, offset);
+    }
+
+    fn pad_cols(&mut self, len: usize) {
+        self.pad_row(len);
+        self.rem.pad_cols(len);
+    }
+}
+
+impl<T, R, S: RowStorage<T>> HMat<T, R, S> {
+    /// Grows the head row to the length `len` if it is shorter.
+    fn pad_row(&mut self, len: usize) {
+        if self.head_row.len() < len {
+            self.head_row.resize(len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn append_cols() {
+        let mut world = World::new(
+            HMat::<usize, ()>::new_with([Some(0), Some(1), Some(2)])
+                .extend_with::<f32, _>([Some(0.5)]),
+        );
+        let entity = world.spawn();
+        let mut batch = World::new(
+            HMat::<usize, ()>::new_with([None, Some(4)]).extend_with::<f32, _>([
+                Some(3.5),
+                None,
+                Some(5.5),
+            ]),
+        );
+        let batch_entity = batch.spawn();
+        // The columns are moved after the spawned entity, aligned and padded even though the rows have different lengths.
+        assert_eq!(world.append_cols(&mut batch), 4..7);
+        assert_eq!(
+            world.get_row_ref::<usize, _>(),
+            &Row::from_iter([Some(0), Some(1), Some(2), None, None, Some(4), None])
+        );
+        assert_eq!(
+            world.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(0.5), None, None, None, Some(3.5), None, Some(5.5)])
+        );
+        assert_eq!(world.num_cols(), 7);
+        // The appended columns are never handed out by `spawn`.
+        assert!(world.is_alive(entity));
+        assert_eq!(world.spawn().index(), 7);
+        // The source matrix is left empty, and its entities are despawned.
+        assert_eq!(batch.num_cols(), 0);
+        assert!(!batch.is_alive(batch_entity));
+        assert_eq!(world.append_cols(&mut batch), 8..8);
+    }
+
+    #[test]
+    fn append_cols_trailing() {
+        let mut mat = HMat::<usize, ()>::new().extend::<f32>();
+        let mut other = HMat::<usize, ()>::new_with([Some(1), None, None]).extend::<f32>();
+        // Every row is padded to the new width, including the trailing empty columns.
+        assert_eq!(mat.append_cols(&mut other), 0..3);
+        assert_eq!(mat.num_cols(), 3);
+        assert_eq!(
+            mat.get_row_ref::<usize, _>(),
+            &Row::from_iter([Some(1), None, None])
+        );
+        assert_eq!(mat.get_row_ref::<f32, _>().len(), 3);
+        assert_eq!(mat.append_cols(&mut other), 3..3);
+    }
+}
@@ -0,0 +1,143 @@
+use std::{iter::Enumerate, slice};
+
+use serde::{Deserialize, Serialize};
+
+/// A growable set of indexes, implemented as a vector of 64-bit words.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BitSet {
+    pub(crate) words: Vec<u64>,
+}
+
+impl PartialEq for BitSet {
+    /// Compares the sets of indexes, ignoring the trailing empty words.
+    fn eq(&self, other: &Self) -> bool {
+        let (short, long) = if self.words.len() <= other.words.len() {
+            (&self.words, &other.words)
+        } else {
+            (&other.words, &self.words)
+        };
+        long[..short.len()] == short[..] && long[short.len()..].iter().all(|&word| word == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl BitSet {
+    /// Returns `true` if the given index is in the set.
+    pub fn contains(&self, idx: usize) -> bool {
+        self.words
+            .get(idx / 64)
+            .is_some_and(|word| word & (1 << (idx % 64)) != 0)
+    }
+
+    /// Adds the given index to the set. Returns `true` if it was not in the set before.
+    pub fn insert(&mut self, idx: usize) -> bool {
+        if idx / 64 >= self.words.len() {
+            self.words.resize(idx / 64 + 1, 0);
+        }
+        let word = &mut self.words[idx / 64];
+        let was_absent = *word & (1 << (idx % 64)) == 0;
+        *word |= 1 << (idx % 64);
+        was_absent
+    }
+
+    /// Removes the given index from the set. Returns `true` if it was in the set before.
+    pub fn remove(&mut self, idx: usize) -> bool {
+        match self.words.get_mut(idx / 64) {
+            Some(word) if *word & (1 << (idx % 64)) != 0 => {
+                *word &= !(1 << (idx % 64));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the number of indexes in the set.
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the smallest index in the set that is not less than `from`.
+    pub fn next_set(&self, from: usize) -> Option<usize> {
+        let mut word_idx = from / 64;
+        let mut word = self.words.get(word_idx)? & (!0 << (from % 64));
+        while word == 0 {
+            word_idx += 1;
+            word = *self.words.get(word_idx)?;
+        }
+        Some(word_idx * 64 + word.trailing_zeros() as usize)
+    }
+
+    /// Returns the smallest index not in the set that is not less than `from`.
+    pub fn next_unset(&self, from: usize) -> usize {
+        let inverted_word = |word_idx: usize| !self.words.get(word_idx).copied().unwrap_or(0);
+        let mut word_idx = from / 64;
+        let mut word = inverted_word(word_idx) & (!0 << (from % 64));
+        while word == 0 {
+            word_idx += 1;
+            word = inverted_word(word_idx);
+        }
+        word_idx * 64 + word.trailing_zeros() as usize
+    }
+
+    /// Returns an iterator over the indexes in the set, in increasing order.
+    pub fn iter(&self) -> BitSetIter<'_> {
+        BitSetIter {
+            words: self.words.iter().enumerate(),
+            word_idx: 0,
+            word: 0,
+        }
+    }
+}
+
+/// An iterator over the indexes in a `BitSet`, in increasing order.
+pub struct BitSetIter<'a> {
+    pub(crate) words: Enumerate<slice::Iter<'a, u64>>,
+    /// The index of the current word.
+    pub(crate) word_idx: usize,
+    /// The bits of the current word that are not visited yet.
+    pub(crate) word: u64,
+}
+
+impl Iterator for BitSetIter<'_> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.word == 0 {
+            (self.word_idx, self.word) = self.words.next().map(|(idx, &word)| (idx, word))?;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        self.word &= self.word - 1;
+        Some(self.word_idx * 64 + bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn bitset() {
+        let mut set = BitSet::default();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(64));
+        assert!(set.insert(130));
+        assert!(set.contains(64) && !set.contains(65));
+        assert_eq!(set.count(), 3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 64, 130]);
+        assert_eq!(set.next_set(4), Some(64));
+        assert_eq!(set.next_set(131), None);
+        assert_eq!(set.next_unset(3), 4);
+        assert_eq!(set.next_unset(200), 200);
+        // Removing the last index leaves an empty word behind, which does not affect equality.
+        assert!(set.remove(130));
+        assert!(!set.remove(130));
+        let mut other = BitSet::default();
+        other.insert(64);
+        other.insert(3);
+        assert_eq!(set, other);
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+/// A handle to a column of a `World`. The handle becomes stale once its column is despawned, even if the column index is reused later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Entity {
+    pub(crate) index: usize,
+    pub(crate) generation: u32,
+}
+
+impl Entity {
+    /// Returns the column index of this entity.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the generation of this entity, i.e., the number of times its column index was despawned before it was spawned.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// A single column slot of an `EntityAllocator`.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct EntitySlot {
+    pub(crate) generation: u32,
+    pub(crate) alive: bool,
+}
+
+/// Keeps track of the alive columns and their generations, reusing the column indexes of the despawned entities.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EntityAllocator {
+    pub(crate) slots: Vec<EntitySlot>,
+    pub(crate) free: Vec<usize>,
+}
+
+impl EntityAllocator {
+    /// Allocates a new entity, reusing the column index of a despawned entity if possible.
+    pub fn spawn(&mut self) -> Entity {
+        let index = self.free.pop().unwrap_or_else(|| {
+            self.slots.push(EntitySlot::default());
+            self.slots.len() - 1
+        });
+        let slot = &mut self.slots[index];
+        slot.alive = true;
+        Entity {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    /// Deallocates the given entity, making its column index available for reuse. Returns `false` if the entity was not alive.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        self.despawn_at(entity.index)
+    }
+
+    /// Deallocates the entity at the given column index `idx` regardless of its generation. Returns `false` if there was no alive entity.
+    pub(crate) fn despawn_at(&mut self, idx: usize) -> bool {
+        match self.slots.get_mut(idx) {
+            Some(slot) if slot.alive => {
+                slot.alive = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(idx);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Marks the columns up to `num_cols` that are not tracked yet as alive, so that they are never handed out by `spawn`.
+    pub(crate) fn reserve(&mut self, num_cols: usize) {
+        if num_cols > self.slots.len() {
+            self.slots.resize(
+                num_cols,
+                EntitySlot {
+                    generation: 0,
+                    alive: true,
+                },
+            );
+        }
+    }
+
+    /// Returns `true` if the given entity is alive, i.e., it was spawned and has not been despawned yet.
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.slots
+            .get(entity.index)
+            .is_some_and(|slot| slot.alive && slot.generation == entity.generation)
+    }
+
+    /// Returns the alive entity at the given column index `idx`.
+    pub fn entity_at(&self, idx: usize) -> Option<Entity> {
+        self.slots
+            .get(idx)
+            .filter(|slot| slot.alive)
+            .map(|slot| Entity {
+                index: idx,
+                generation: slot.generation,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn col(f: Option<f32>, u: Option<usize>) -> HCol<f32, HCol<usize, ()>> {
+        HCol {
+            elem: f,
+            rem: HCol { elem: u, rem: () },
+        }
+    }
+
+    #[test]
+    fn entity() {
+        let mut mat = World::new(HMat::<usize, ()>::new().extend::<f32>());
+        let e0 = mat.spawn();
+        let e1 = mat.spawn();
+        mat.place_entity_col(e0, col(Some(0.5), Some(0))).unwrap();
+        mat.place_entity_col(e1, col(None, Some(1))).unwrap();
+        assert!(mat.is_alive(e0) && mat.is_alive(e1));
+        assert_eq!(mat.entity_at(1), Some(e1));
+        // Despawning moves the column out of the matrix.
+        assert_eq!(mat.despawn(e0), Some(col(Some(0.5), Some(0))));
+        assert!(!mat.is_alive(e0));
+        assert_eq!(mat.despawn(e0), None);
+        // The column index is reused with a new generation, so the stale handle does not alias it.
+        let e2 = mat.spawn();
+        assert_eq!(e2.index(), e0.index());
+        assert_ne!(e2.generation(), e0.generation());
+        mat.place_entity_col(e2, col(Some(2.5), Some(2))).unwrap();
+        assert!(mat.get_entity_col_ref(e0).is_none());
+        assert!(mat.get_entity_col_mut(e0).is_none());
+        assert_eq!(mat.take_entity_col(e0), None);
+        assert_eq!(
+            mat.place_entity_col(e0, col(Some(-1.0), None)),
+            Err(col(Some(-1.0), None))
+        );
+        // The handles of the alive entities work as usual.
+        if let Some(col) = mat.get_entity_col_mut(e2) {
+            if let Some(val) = col.elem {
+                *val += 1.0;
+            }
+        }
+        let col_ref = mat.get_entity_col_ref(e2).unwrap();
+        assert_eq!(col_ref.elem, Some(&3.5));
+        assert_eq!(mat.take_entity_col(e2), Some(col(Some(3.5), Some(2))));
+        assert!(mat.is_alive(e2));
+    }
+
+    #[test]
+    fn push_remove_col() {
+        let mut mat = World::new(
+            HMat::<usize, ()>::new_with([Some(0)]).extend_with::<f32, _>([None, Some(1.5)]),
+        );
+        // The existing columns are never overwritten.
+        assert_eq!(mat.push_col(col(Some(2.5), Some(2))), 2);
+        assert_eq!(mat.push_col(col(None, Some(3))), 3);
+        // Removing a column makes its slot available for the next push.
+        assert_eq!(mat.remove_col(1), col(Some(1.5), None));
+        assert_eq!(mat.remove_col(3), col(None, Some(3)));
+        assert_eq!(mat.push_col(col(Some(4.5), None)), 3);
+        assert_eq!(mat.push_col(col(Some(5.5), None)), 1);
+        assert_eq!(mat.push_col(col(Some(6.5), None)), 4);
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([None, Some(5.5), Some(2.5), Some(4.5), Some(6.5)])
+        );
+        // Removing a free slot twice does not hand it out twice.
+        mat.remove_col(0);
+        mat.remove_col(0);
+        assert_eq!(mat.push_col(col(None, None)), 0);
+        assert_eq!(mat.push_col(col(None, None)), 5);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{HMat, Row};
+use crate::{HMat, Row, RowStorage};
 
 /// Represents a type that can be extended with new types.
 pub trait Extend {
@@ -9,10 +9,14 @@ pub trait Extend {
     fn extend_with<E, I>(self, iter: I) -> HMat<E, Self::Old>
     where
         I: IntoIterator<Item = Option<E>>;
+    /// Extends this `HMat<T, _>` with a new empty row of type `E` stored in `Se`, returning `HMat<E, HMat<T, _>, Se>`.
+    fn extend_with_storage<E, Se>(self) -> HMat<E, Self::Old, Se>
+    where
+        Se: RowStorage<E>;
 }
 
-impl<T, R> Extend for HMat<T, R> {
-    type Old = HMat<T, R>;
+impl<T, R, S> Extend for HMat<T, R, S> {
+    type Old = HMat<T, R, S>;
     fn extend<E>(self) -> HMat<E, Self::Old> {
         HMat {
             head_row: Default::default(),
@@ -29,6 +33,16 @@ impl<T, R> Extend for HMat<T, R> {
             rem: self,
         }
     }
+
+    fn extend_with_storage<E, Se>(self) -> HMat<E, Self::Old, Se>
+    where
+        Se: RowStorage<E>,
+    {
+        HMat {
+            head_row: Default::default(),
+            rem: self,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::Flatten;
+
 mod sub_col;
 
 use sub_col::*;
@@ -12,6 +14,24 @@ pub struct HCol<T, R> {
 }
 
 impl<T, R> HCol<T, R> {
+    /// Creates a new column from its first element and the remaining column.
+    pub fn new(elem: Option<T>, rem: R) -> Self {
+        Self { elem, rem }
+    }
+
+    /// Returns the first element and the remaining column.
+    pub fn into_inner(self) -> (Option<T>, R) {
+        (self.elem, self.rem)
+    }
+
+    /// Converts this column into a flat tuple of its elements, e.g., `(Option<A>, Option<B>)`.
+    pub fn into_tuple(self) -> <Self as Flatten>::Flat
+    where
+        Self: Flatten,
+    {
+        self.flatten()
+    }
+
     fn get_first(&self) -> Option<&T> {
         self.elem.as_ref()
     }
@@ -29,7 +49,7 @@ impl<T, R> HCol<T, R> {
     }
 
     /// Returns a reference to the element of type `D` in this column.
-    pub fn get<'a, D, A>(&'a self) -> Option<&D>
+    pub fn get<'a, D, A>(&'a self) -> Option<&'a D>
     where
         Self: GetSubCol<'a, D, A>,
     {
@@ -37,7 +57,7 @@ impl<T, R> HCol<T, R> {
     }
 
     /// Returns a mutable reference to the element of type `D` in this column.
-    pub fn get_mut<'a, D, A>(&'a mut self) -> Option<&mut D>
+    pub fn get_mut<'a, D, A>(&'a mut self) -> Option<&'a mut D>
     where
         Self: GetSubCol<'a, D, A>,
     {
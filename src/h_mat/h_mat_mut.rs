@@ -0,0 +1,93 @@
+use std::marker::PhantomData;
+
+use crate::{
+    AccessColMut, AccessColRef, AccessRowMut, AccessRowRef, HCol, HColIterMut, NumCols,
+    SplitRowsMut,
+};
+
+use super::{HColIter, Row};
+
+/// A mutable reference to a `HMat` with arbitrarily ordered, pairwise distinct rows.
+#[derive(Debug)]
+pub struct HMatMut<'a, D, R, S = Vec<Option<D>>> {
+    pub(crate) row: &'a mut Row<D, S>,
+    pub(crate) rem: R,
+}
+
+impl<'a, T, R, S> HMatMut<'a, T, R, S> {
+    /// Returns a reference to the `Row<D>` of this matrix slice.
+    pub fn get_row_ref<D, Directive>(
+        &self,
+    ) -> &Row<D, <Self as AccessRowRef<D, Directive>>::Storage>
+    where
+        Self: AccessRowRef<D, Directive>,
+    {
+        AccessRowRef::<D, Directive>::get_row_ref(self)
+    }
+
+    /// Returns a mutable reference to the `Row<D>` of this matrix slice.
+    pub fn get_row_mut<D, Directive>(
+        &mut self,
+    ) -> &mut Row<D, <Self as AccessRowMut<D, Directive>>::Storage>
+    where
+        Self: AccessRowMut<D, Directive>,
+    {
+        AccessRowMut::<D, Directive>::get_row_mut(self)
+    }
+
+    /// Returns a reference to the column at the given column index `col_idx`.
+    pub fn get_col_ref<'b>(
+        &'b self,
+        col_idx: usize,
+    ) -> HCol<&'b T, <Self as AccessColRef<'b, T>>::Rem>
+    where
+        Self: AccessColRef<'b, T>,
+    {
+        AccessColRef::<'b, T>::get_col_ref(self, col_idx)
+    }
+
+    /// Returns a mutable reference to the column at the given column index `col_idx`.
+    pub fn get_col_mut<'b>(
+        &'b mut self,
+        col_idx: usize,
+    ) -> HCol<&'b mut T, <Self as AccessColMut<'b, T>>::Rem>
+    where
+        Self: AccessColMut<'b, T>,
+    {
+        AccessColMut::<'b, T>::get_col_mut(self, col_idx)
+    }
+
+    /// Returns the number of columns of this matrix slice, i.e., the length of its longest row.
+    pub fn num_cols(&self) -> usize
+    where
+        Self: NumCols,
+    {
+        NumCols::num_cols(self)
+    }
+
+    /// Returns an iterator that iterates over all the columns, with the column indexes from `0` to `num_cols()-1`.
+    pub fn iter<'b>(&'b self) -> HColIter<'b, Self, T>
+    where
+        Self: AccessColRef<'b, T> + NumCols,
+    {
+        HColIter {
+            mat_ref: self,
+            curr_col_idx: 0,
+            num_cols: self.num_cols(),
+            pd: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that iterates over all the columns as mutable references, with the column indexes from `0` to `num_cols()-1`.
+    pub fn iter_mut<'b>(&'b mut self) -> HColIterMut<<Self as SplitRowsMut<'b>>::Rows>
+    where
+        Self: SplitRowsMut<'b> + NumCols,
+    {
+        let num_cols = self.num_cols();
+        HColIterMut {
+            rows: self.split_rows_mut(),
+            curr_col_idx: 0,
+            num_cols,
+        }
+    }
+}
@@ -1,40 +1,130 @@
-use crate::{AccessColRef, AccessRowRef, HCol};
+use std::marker::PhantomData;
+
+use crate::{
+    AccessColRef, AccessRowRef, Append, Commands, FetchCol, FetchQuery, HCol, HMatWriter, Local,
+    NewWriter, NumCols, PatchSet, QueryIter, QueryRef, Sendable, WriteOrder,
+};
 
 use super::{HColIter, Row};
 
 /// A reference to a `HMat` with arbitrarily ordered rows.
 #[derive(Clone, Copy, Debug)]
-pub struct HMatRef<'a, D, R> {
-    pub(crate) row: &'a Row<D>,
+pub struct HMatRef<'a, D, R, S = Vec<Option<D>>> {
+    pub(crate) row: &'a Row<D, S>,
     pub(crate) rem: R,
 }
 
-impl<'a, T, R> HMatRef<'a, T, R> {
+impl<'a, T, R, S> HMatRef<'a, T, R, S> {
     /// Returns a reference to the `Row<D>` of this matrix slice.
-    pub fn get_row_ref<D, Directive>(&self) -> &Row<D>
+    pub fn get_row_ref<D, Directive>(
+        &self,
+    ) -> &Row<D, <Self as AccessRowRef<D, Directive>>::Storage>
     where
         Self: AccessRowRef<D, Directive>,
     {
         AccessRowRef::<D, Directive>::get_row_ref(self)
     }
 
+    /// Concatenates the rows of this matrix slice and the rows of `other`, whose row types must be disjoint from the ones of this slice.
+    pub fn append<Other, Directive>(
+        self,
+        other: Other,
+    ) -> <Self as Append<Other, Directive>>::Output
+    where
+        Self: Append<Other, Directive>,
+    {
+        Append::<Other, Directive>::append(self, other)
+    }
+
     /// Returns a reference to the column at the given column index `col_idx`.
-    pub fn get_col_ref(&'a self, col_idx: usize) -> HCol<&T, <Self as AccessColRef<'a, T>>::Rem>
+    pub fn get_col_ref(&'a self, col_idx: usize) -> HCol<&'a T, <Self as AccessColRef<'a, T>>::Rem>
     where
         Self: AccessColRef<'a, T>,
     {
         AccessColRef::<'a, T>::get_col_ref(self, col_idx)
     }
 
-    /// Returns an iterator that iterates over `num_cols` many columns, with the column indexes from `0` to `num_cols-1`.
-    pub fn iter(&'a self, num_cols: usize) -> HColIter<'a, Self>
+    /// Returns the number of columns of this matrix slice, i.e., the length of its longest row.
+    pub fn num_cols(&self) -> usize
     where
-        Self: AccessColRef<'a, T>,
+        Self: NumCols,
+    {
+        NumCols::num_cols(self)
+    }
+
+    /// Returns an iterator that iterates over all the columns, with the column indexes from `0` to `num_cols()-1`.
+    pub fn iter(&'a self) -> HColIter<'a, Self, T>
+    where
+        Self: AccessColRef<'a, T> + NumCols,
     {
         HColIter {
             mat_ref: self,
             curr_col_idx: 0,
-            num_cols,
+            num_cols: self.num_cols(),
+            pd: PhantomData,
         }
     }
+
+    /// Returns an iterator that iterates over the columns whose elements are all present in the rows of this slice, yielding them as tuples prefixed with the column index, e.g., `(usize, &T, ...)`.
+    pub fn query_all(&self) -> QueryIter<&Self>
+    where
+        Self: FetchCol + NumCols,
+    {
+        QueryIter {
+            mat_ref: self,
+            curr_col_idx: 0,
+            num_cols: self.num_cols(),
+        }
+    }
+
+    /// Returns an iterator that iterates over the columns matching the query `Q`, yielding them as tuples prefixed with the column index.
+    /// The fields of `Q` are either row types, which must be present, or marked as `Opt<T>`, `With<T>` or `Without<T>`.
+    pub fn query<'b, Q, Directive>(&'b self) -> QueryIter<QueryRef<'b, Self, Q, Directive>>
+    where
+        Self: FetchQuery<'b, Q, Directive> + NumCols,
+    {
+        QueryIter {
+            mat_ref: QueryRef {
+                mat_ref: self,
+                pd: PhantomData,
+            },
+            curr_col_idx: 0,
+            num_cols: self.num_cols(),
+        }
+    }
+
+    /// Returns a new writer that can be used to gather modifications and apply them at once, ordered by their kinds.
+    pub fn new_writer(&self) -> HMatWriter<T, <Self as NewWriter<T, Local>>::Rem>
+    where
+        Self: NewWriter<T, Local>,
+    {
+        self.new_writer_with_order(WriteOrder::Priority)
+    }
+
+    /// Returns a new writer that applies the modifications in the order they were queued.
+    pub fn new_writer_ordered(&self) -> HMatWriter<T, <Self as NewWriter<T, Local>>::Rem>
+    where
+        Self: NewWriter<T, Local>,
+    {
+        self.new_writer_with_order(WriteOrder::Insertion)
+    }
+
+    /// Returns a new writer that is `Send` and `Sync`, provided that the elements are.
+    pub fn new_send_writer(&self) -> HMatWriter<T, <Self as NewWriter<T, Sendable>>::Rem, Sendable>
+    where
+        Self: NewWriter<T, Sendable>,
+    {
+        self.new_writer_with_order(WriteOrder::Priority)
+    }
+
+    /// Returns a new writer that holds data-only patches instead of closures, so that it can be serialized. `P` maps each row type to its patch type, see `PatchSet`.
+    pub fn new_command_writer<P>(
+        &self,
+    ) -> HMatWriter<T, <Self as NewWriter<T, Commands<P>>>::Rem, Commands<P>>
+    where
+        Self: NewWriter<T, Commands<P>>,
+        P: PatchSet<T>,
+    {
+        self.new_writer_with_order(WriteOrder::Priority)
+    }
 }
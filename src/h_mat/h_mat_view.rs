@@ -0,0 +1,103 @@
+use std::marker::PhantomData;
+
+use crate::{AccessColView, AccessRowMut, AccessRowRef, NumCols, RowStorage};
+
+use super::Row;
+
+/// Marks a row of a `HMatView` as read-only, i.e., borrowed as `&Row<T, S>`.
+#[derive(Clone, Copy, Debug)]
+pub struct Read<T, S = Vec<Option<T>>>(PhantomData<*const (T, S)>);
+
+/// Marks a row of a `HMatView` as writable, i.e., borrowed as `&mut Row<T, S>`.
+#[derive(Clone, Copy, Debug)]
+pub struct Write<T, S = Vec<Option<T>>>(PhantomData<*const (T, S)>);
+
+/// Represents the way a row of a `HMatView` is borrowed.
+pub trait AccessMode<'a> {
+    /// The element type of the row.
+    type Elem: 'a;
+    /// The storage of the row.
+    type Storage: RowStorage<Self::Elem> + 'a;
+    /// The borrowed row.
+    type RowRef: 'a;
+    /// Converts the mutable reference to the row into the borrowed row.
+    fn from_row_mut(row: &'a mut Row<Self::Elem, Self::Storage>) -> Self::RowRef;
+    /// Returns the borrowed row as a reference.
+    fn row_ref(row: &Self::RowRef) -> &Row<Self::Elem, Self::Storage>;
+}
+
+impl<'a, T: 'a, S: RowStorage<T> + 'a> AccessMode<'a> for Read<T, S> {
+    type Elem = T;
+    type Storage = S;
+    type RowRef = &'a Row<T, S>;
+    fn from_row_mut(row: &'a mut Row<T, S>) -> Self::RowRef {
+        row
+    }
+
+    fn row_ref(row: &Self::RowRef) -> &Row<T, S> {
+        row
+    }
+}
+
+impl<'a, T: 'a, S: RowStorage<T> + 'a> AccessMode<'a> for Write<T, S> {
+    type Elem = T;
+    type Storage = S;
+    type RowRef = &'a mut Row<T, S>;
+    fn from_row_mut(row: &'a mut Row<T, S>) -> Self::RowRef {
+        row
+    }
+
+    fn row_ref(row: &Self::RowRef) -> &Row<T, S> {
+        row
+    }
+}
+
+/// A view to a `HMat` with arbitrarily ordered, pairwise distinct rows, each of which is marked as either `Read` or `Write`.
+pub struct HMatView<'a, M, R>
+where
+    M: AccessMode<'a>,
+{
+    pub(crate) row: M::RowRef,
+    pub(crate) rem: R,
+}
+
+impl<'a, M, R> HMatView<'a, M, R>
+where
+    M: AccessMode<'a>,
+{
+    /// Returns a reference to the `Row<D>` of this view, which may be marked as either `Read` or `Write`.
+    pub fn get_row_ref<D, Directive>(
+        &self,
+    ) -> &Row<D, <Self as AccessRowRef<D, Directive>>::Storage>
+    where
+        Self: AccessRowRef<D, Directive>,
+    {
+        AccessRowRef::<D, Directive>::get_row_ref(self)
+    }
+
+    /// Returns a mutable reference to the `Row<D>` of this view, which must be marked as `Write`.
+    pub fn get_row_mut<D, Directive>(
+        &mut self,
+    ) -> &mut Row<D, <Self as AccessRowMut<D, Directive>>::Storage>
+    where
+        Self: AccessRowMut<D, Directive>,
+    {
+        AccessRowMut::<D, Directive>::get_row_mut(self)
+    }
+
+    /// Returns the column at the given column index `col_idx`, with references for the `Read` rows and mutable references for the `Write` rows.
+    pub fn get_col<'b>(&'b mut self, col_idx: usize) -> <Self as AccessColView<'b>>::Col
+    where
+        Self: AccessColView<'b>,
+    {
+        AccessColView::<'b>::get_col(self, col_idx)
+    }
+
+    /// Returns the number of columns of this view, i.e., the length of its longest row.
+    pub fn num_cols(&self) -> usize
+    where
+        Self: NumCols,
+    {
+        NumCols::num_cols(self)
+    }
+}
@@ -1,17 +1,21 @@
-use crate::{AccessColRef, HCol, HMatRef};
+use std::marker::PhantomData;
 
-/// An iterator that iterates over the columns of `H`.
-pub struct HColIter<'a, H> {
+use crate::{AccessColRef, HCol};
+
+/// An iterator that iterates over the columns of `H`, whose first row is of type `T`.
+pub struct HColIter<'a, H, T> {
     pub(crate) mat_ref: &'a H,
     pub(crate) curr_col_idx: usize,
     pub(crate) num_cols: usize,
+    pub(crate) pd: PhantomData<T>,
 }
 
-impl<'a, T, R> Iterator for HColIter<'a, HMatRef<'a, T, R>>
+impl<'a, H, T> Iterator for HColIter<'a, H, T>
 where
-    HMatRef<'a, T, R>: AccessColRef<'a, T>,
+    H: AccessColRef<'a, T>,
+    T: 'a,
 {
-    type Item = HCol<&'a T, <HMatRef<'a, T, R> as AccessColRef<'a, T>>::Rem>;
+    type Item = HCol<&'a T, <H as AccessColRef<'a, T>>::Rem>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let col_idx = self.curr_col_idx;
@@ -23,3 +27,51 @@ where
         Some(col)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn iter() {
+        let mat = HMat::<usize, ()>::new_with([Some(0), Some(1), Some(2)])
+            .extend_with::<f32, _>([None, Some(0.5)])
+            .extend_with::<i32, _>([None, None, Some(-1)]);
+        // Iterate over the columns of the whole matrix.
+        let cols: Vec<_> = mat.iter().collect();
+        assert_eq!(cols.len(), 3);
+        assert_eq!(cols[0].rem.rem.elem, Some(&0));
+        assert_eq!(cols[1].rem.elem, Some(&0.5));
+        assert_eq!(cols[2].rem.elem, None);
+        assert_eq!(cols[2].elem, Some(&-1));
+        // ... or over the columns of a slice.
+        let ref_mat: HMatRef<i32, HMatRef<usize, ()>> = mat.slice();
+        let cols: Vec<HCol<&i32, HCol<&usize, ()>>> = ref_mat.iter().collect();
+        assert_eq!(
+            cols,
+            vec![
+                HCol {
+                    elem: None,
+                    rem: HCol {
+                        elem: Some(&0),
+                        rem: ()
+                    }
+                },
+                HCol {
+                    elem: None,
+                    rem: HCol {
+                        elem: Some(&1),
+                        rem: ()
+                    }
+                },
+                HCol {
+                    elem: Some(&-1),
+                    rem: HCol {
+                        elem: Some(&2),
+                        rem: ()
+                    }
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{HCol, HMat, HMatMut, RowStorage, SeekMut};
+
+/// A cursor over the elements of a single row, followed by the cursors of the remaining rows `R`.
+pub struct RowIterMut<C, R> {
+    pub(crate) cursor: C,
+    pub(crate) rem: R,
+}
+
+/// Represents a type whose rows can be split into mutable element cursors.
+pub trait SplitRowsMut<'a> {
+    /// The recursive `RowIterMut` of the rows.
+    type Rows;
+    /// Splits the rows into a recursive `RowIterMut`, borrowing every row mutably at once.
+    fn split_rows_mut(&'a mut self) -> Self::Rows;
+}
+
+/// Represents a recursive row cursor that can yield the columns as mutable references.
+pub trait NextColMut {
+    /// The type of the yielded columns.
+    type Col;
+    /// Advances every row cursor to the given column index `col_idx`, which must be increasing across the calls, and returns the column.
+    fn next_col_mut(&mut self, col_idx: usize) -> Self::Col;
+}
+
+/// An iterator that iterates over the columns of a matrix as mutable references.
+pub struct HColIterMut<Rows> {
+    pub(crate) rows: Rows,
+    pub(crate) curr_col_idx: usize,
+    pub(crate) num_cols: usize,
+}
+
+impl SplitRowsMut<'_> for () {
+    type Rows = ();
+    fn split_rows_mut(&mut self) -> Self::Rows {}
+}
+
+impl<'a, T, R, S> SplitRowsMut<'a> for HMat<T, R, S>
+where
+    T: 'a,
+    S: RowStorage<T> + 'a,
+    R: SplitRowsMut<'a>,
+{
+    type Rows = RowIterMut<S::CursorMut<'a>, R::Rows>;
+    fn split_rows_mut(&'a mut self) -> Self::Rows {
+        RowIterMut {
+            cursor: self.head_row.0.cursor_mut(),
+            rem: self.rem.split_rows_mut(),
+        }
+    }
+}
+
+impl<'a, 'b, T, R, S> SplitRowsMut<'a> for HMatMut<'b, T, R, S>
+where
+    'b: 'a,
+    S: RowStorage<T>,
+    R: SplitRowsMut<'a>,
+{
+    type Rows = RowIterMut<S::CursorMut<'a>, R::Rows>;
+    fn split_rows_mut(&'a mut self) -> Self::Rows {
+        RowIterMut {
+            cursor: self.row.0.cursor_mut(),
+            rem: self.rem.split_rows_mut(),
+        }
+    }
+}
+
+impl NextColMut for () {
+    type Col = ();
+    fn next_col_mut(&mut self, _col_idx: usize) -> Self::Col {}
+}
+
+impl<C, R> NextColMut for RowIterMut<C, R>
+where
+    C: SeekMut,
+    R: NextColMut,
+{
+    type Col = HCol<C::Item, R::Col>;
+    fn next_col_mut(&mut self, col_idx: usize) -> Self::Col {
+        HCol {
+            elem: self.cursor.seek_mut(col_idx),
+            rem: self.rem.next_col_mut(col_idx),
+        }
+    }
+}
+
+impl<Rows: NextColMut> Iterator for HColIterMut<Rows> {
+    type Item = Rows::Col;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.curr_col_idx == self.num_cols {
+            return None;
+        }
+        let col = self.rows.next_col_mut(self.curr_col_idx);
+        self.curr_col_idx += 1;
+        Some(col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn iter_mut() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), Some(1), Some(2)])
+            .extend_with::<f32, _>([None, Some(0.5)])
+            .extend_with::<i32, _>([Some(-1), None, Some(-3)]);
+        // Walk over every column mutably.
+        for mut col in mat.iter_mut() {
+            if let Some(val) = col.get_mut::<&mut i32, _>() {
+                **val *= 10;
+            }
+            if let Some(val) = col.get_mut::<&mut f32, _>() {
+                **val += 1.0;
+            }
+        }
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(-10), None, Some(-30)])
+        );
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([None, Some(1.5)])
+        );
+        // Every column is visited, even when the rows have different lengths.
+        let cols: Vec<_> = mat.iter_mut().collect();
+        assert_eq!(cols.len(), 3);
+        assert_eq!(cols[2].rem.elem, None);
+        assert_eq!(cols[2].rem.rem.elem, Some(&mut 2));
+    }
+
+    #[test]
+    fn iter_mut_storages() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with_storage::<i32, SparseStorage<i32>>()
+            .extend_with_storage::<f32, PackedStorage<f32>>();
+        // The sparse elements are stored out of order, yet every column gets its own elements.
+        mat.get_row_mut::<i32, _>().place(1_000, -2);
+        mat.get_row_mut::<i32, _>().place(1, -1);
+        mat.get_row_mut::<f32, _>().place(1, 0.5);
+        let mut visited = vec![];
+        for (col_idx, col) in mat.iter_mut().enumerate() {
+            if let (Some(val), rem) = (col.rem.elem, col.rem.rem.elem) {
+                *val *= 10;
+                visited.push((col_idx, *val, rem.copied()));
+            }
+        }
+        assert_eq!(visited, vec![(1, -10, Some(1)), (1_000, -20, None)]);
+    }
+}
@@ -0,0 +1,94 @@
+/// Expands to the `HMat` type with the given row types, in the written order, e.g., `hmat_type![i32, f32]` is `HMat<i32, HMat<f32, ()>>`.
+/// The storage of a row can be given after its type, e.g., `hmat_type![i32: SparseStorage<i32>, f32]` is `HMat<i32, HMat<f32, ()>, SparseStorage<i32>>`.
+#[macro_export]
+macro_rules! hmat_type {
+    () => { () };
+    ($head:ty : $storage:ty $(, $($tail:tt)*)?) => {
+        $crate::HMat<$head, $crate::hmat_type![$($($tail)*)?], $storage>
+    };
+    ($head:ty $(, $($tail:tt)*)?) => {
+        $crate::HMat<$head, $crate::hmat_type![$($($tail)*)?]>
+    };
+}
+
+/// Constructs an empty `HMat` with the given row types, in the written order, e.g., `hmat![i32, f32]` is a `HMat<i32, HMat<f32, ()>>`.
+/// Accepts the same storage syntax as `hmat_type!`.
+#[macro_export]
+macro_rules! hmat {
+    ($($row:tt)+) => {
+        <$crate::hmat_type![$($row)+] as ::core::default::Default>::default()
+    };
+}
+
+/// Expands to the `HMatRef` type with the given row types, in the written order, e.g., `hmat_ref_type![i32, f32]` is `HMatRef<'_, i32, HMatRef<'_, f32, ()>>`.
+/// The lifetime can be given explicitly as `hmat_ref_type!['a; i32, f32]`, and the storage of a row after its type as in `hmat_type!`, e.g., `hmat_ref_type![i32: SparseStorage<i32>, f32]`.
+#[macro_export]
+macro_rules! hmat_ref_type {
+    ($lt:lifetime;) => { () };
+    ($lt:lifetime; $head:ty : $storage:ty $(, $($tail:tt)*)?) => {
+        $crate::HMatRef<$lt, $head, $crate::hmat_ref_type![$lt; $($($tail)*)?], $storage>
+    };
+    ($lt:lifetime; $head:ty $(, $($tail:tt)*)?) => {
+        $crate::HMatRef<$lt, $head, $crate::hmat_ref_type![$lt; $($($tail)*)?]>
+    };
+    ($($row:tt)*) => {
+        $crate::hmat_ref_type!['_; $($row)*]
+    };
+}
+
+/// Expands to the `HCol` type with the given element types, in the written order, e.g., `hcol_type![i32, f32]` is `HCol<i32, HCol<f32, ()>>`.
+#[macro_export]
+macro_rules! hcol_type {
+    () => { () };
+    ($head:ty $(, $tail:ty)* $(,)?) => {
+        $crate::HCol<$head, $crate::hcol_type![$($tail),*]>
+    };
+}
+
+/// Expands to the `HMatWriter` type with the given row types, in the written order, e.g., `hmat_writer_type![i32, f32]` is `HMatWriter<i32, HMatWriter<f32, ()>>`.
+/// The writer kind can be given explicitly as `hmat_writer_type![Sendable; i32, f32]`.
+#[macro_export]
+macro_rules! hmat_writer_type {
+    ($kind:ty;) => { () };
+    ($kind:ty; $head:ty $(, $tail:ty)* $(,)?) => {
+        $crate::HMatWriter<$head, $crate::hmat_writer_type![$kind; $($tail),*], $kind>
+    };
+    ($($row:ty),* $(,)?) => {
+        $crate::hmat_writer_type![$crate::Local; $($row),*]
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn macros() {
+        let mut mat: hmat_type![i32, f32, usize] = hmat![i32, f32, usize];
+        let built: HMat<i32, HMat<f32, HMat<usize, ()>>> =
+            HMat::<usize, ()>::new().extend::<f32>().extend::<i32>();
+        assert_eq!(mat.get_row_ref::<f32, _>(), built.get_row_ref::<f32, _>());
+        mat.get_row_mut::<usize, _>().place(0, 1);
+        let col: hcol_type![&i32, &f32, &usize] = mat.get_col_ref(0);
+        assert_eq!(col.rem.rem.elem, Some(&1));
+        let ref_mat: hmat_ref_type![f32, usize] = mat.slice();
+        let mut writer: hmat_writer_type![f32, usize] = ref_mat.new_writer();
+        writer.set_col(1, 0.5);
+        mat.apply(writer);
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([None, Some(0.5)])
+        );
+        // Rows can pick their storage.
+        let mut mat: hmat_type![i32: SparseStorage<i32>, f32: PackedStorage<f32>, usize,] =
+            hmat![i32: SparseStorage<i32>, f32: PackedStorage<f32>, usize,];
+        mat.get_row_mut::<i32, _>().place(1_000_000, -1);
+        let i32_row: &SparseRow<i32> = mat.get_row_ref();
+        assert_eq!(i32_row.get(1_000_000), Some(&-1));
+        let f32_row: &PackedRow<f32> = mat.get_row_ref();
+        assert_eq!(f32_row.len(), 0);
+        let _: &Row<usize> = mat.get_row_ref();
+        let ref_mat: hmat_ref_type![f32: PackedStorage<f32>, i32: SparseStorage<i32>] = mat.slice();
+        assert_eq!(ref_mat.num_cols(), 1_000_001);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{AccessMode, HMat, HMatMut, HMatRef, HMatView, RowStorage};
+
+/// Represents a type that knows its number of columns.
+pub trait NumCols {
+    /// Returns the number of columns, i.e., the maximum length of the rows.
+    fn num_cols(&self) -> usize;
+}
+
+impl NumCols for () {
+    fn num_cols(&self) -> usize {
+        0
+    }
+}
+
+impl<T, R, S> NumCols for HMat<T, R, S>
+where
+    S: RowStorage<T>,
+    R: NumCols,
+{
+    fn num_cols(&self) -> usize {
+        self.head_row.len().max(self.rem.num_cols())
+    }
+}
+
+impl<'a, T, R, S> NumCols for HMatRef<'a, T, R, S>
+where
+    S: RowStorage<T>,
+    R: NumCols,
+{
+    fn num_cols(&self) -> usize {
+        self.row.len().max(self.rem.num_cols())
+    }
+}
+
+impl<'a, T, R, S> NumCols for HMatMut<'a, T, R, S>
+where
+    S: RowStorage<T>,
+    R: NumCols,
+{
+    fn num_cols(&self) -> usize {
+        self.row.len().max(self.rem.num_cols())
+    }
+}
+
+impl<'a, M, R> NumCols for HMatView<'a, M, R>
+where
+    M: AccessMode<'a>,
+    R: NumCols,
+{
+    fn num_cols(&self) -> usize {
+        M::row_ref(&self.row).len().max(self.rem.num_cols())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn num_cols() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with::<f32, _>([None, None, Some(0.5)])
+            .extend::<i32>();
+        assert_eq!(mat.num_cols(), 3);
+        // Placing a column beyond the current width grows the matrix...
+        mat.get_row_mut::<i32, _>().place(4, -1);
+        assert_eq!(mat.num_cols(), 5);
+        // ... whereas placing a column within it does not shrink it.
+        mat.get_row_mut::<i32, _>().place(0, -2);
+        assert_eq!(mat.num_cols(), 5);
+        // Slices only consider their own rows.
+        let ref_mat: HMatRef<usize, HMatRef<f32, ()>> = mat.slice();
+        assert_eq!(ref_mat.num_cols(), 3);
+        assert_eq!(ref_mat.iter().count(), 3);
+        assert_eq!(mat.iter().count(), 5);
+    }
+}
@@ -0,0 +1,346 @@
+use std::{fmt, mem::MaybeUninit};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{BitSet, BitSetIter, Row, RowStorage, SortedCursorMut};
+
+/// A row storage that keeps the elements unwrapped in a vector, next to a bitset that marks the present ones.
+/// Suitable for the small element types, as no space is spent on the `Option` tags, and the runs of present elements can be accessed as contiguous slices.
+pub struct PackedStorage<T> {
+    pub(crate) values: Vec<MaybeUninit<T>>,
+    pub(crate) present: BitSet,
+}
+
+/// A homogenous row backed by a `PackedStorage`.
+pub type PackedRow<T> = Row<T, PackedStorage<T>>;
+
+impl<T> Default for PackedStorage<T> {
+    /// Returns an empty storage.
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+            present: Default::default(),
+        }
+    }
+}
+
+impl<T> Drop for PackedStorage<T> {
+    /// Drops the present elements, leaving the rest untouched.
+    fn drop(&mut self) {
+        for idx in self.present.iter() {
+            // SAFETY: the indexes in `present` mark the initialized elements, and each of them is dropped exactly once here.
+            unsafe { self.values[idx].assume_init_drop() };
+        }
+    }
+}
+
+impl<T: Clone> Clone for PackedStorage<T> {
+    fn clone(&self) -> Self {
+        (0..self.len()).map(|idx| self.get(idx).cloned()).collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PackedStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|idx| self.get(idx)))
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for PackedStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.present == other.present
+            && self.iter().all(|(idx, elem)| other.get(idx) == Some(elem))
+    }
+}
+
+impl<T: Eq> Eq for PackedStorage<T> {}
+
+impl<T: Serialize> Serialize for PackedStorage<T> {
+    /// Serializes the storage in the same format as a `Vec<Option<T>>`.
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq((0..self.len()).map(|idx| self.get(idx)))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PackedStorage<T> {
+    /// Deserializes the storage from the same format as a `Vec<Option<T>>`.
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        Ok(Vec::<Option<T>>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<T> FromIterator<Option<T>> for PackedStorage<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut storage = Self::default();
+        for (idx, opt_elem) in iter.into_iter().enumerate() {
+            match opt_elem {
+                Some(elem) => {
+                    storage.place(idx, elem);
+                }
+                None => storage.values.push(MaybeUninit::uninit()),
+            }
+        }
+        storage
+    }
+}
+
+/// An iterator over the present elements of a `PackedStorage` and their indexes, in increasing order of the indexes.
+pub struct PackedIter<'a, T> {
+    pub(crate) values: &'a [MaybeUninit<T>],
+    pub(crate) present: BitSetIter<'a>,
+}
+
+impl<'a, T> Iterator for PackedIter<'a, T> {
+    type Item = (usize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.present.next()?;
+        // SAFETY: `present` only yields the indexes of the initialized elements, and `values` holds the whole storage.
+        Some((idx, unsafe { self.values[idx].assume_init_ref() }))
+    }
+}
+
+/// An iterator over the present elements of a `PackedStorage` as mutable references and their indexes, in increasing order of the indexes.
+pub struct PackedIterMut<'a, T> {
+    /// The values after the last visited element.
+    pub(crate) values: &'a mut [MaybeUninit<T>],
+    /// The index of the first element of `values`.
+    pub(crate) offset: usize,
+    pub(crate) present: BitSetIter<'a>,
+}
+
+impl<'a, T> Iterator for PackedIterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.present.next()?;
+        let (elem, tail) =
+            std::mem::take(&mut self.values)[idx - self.offset..].split_first_mut()?;
+        self.values = tail;
+        self.offset = idx + 1;
+        // SAFETY: `present` only yields the indexes of the initialized elements in increasing order, and `elem` is split off the values that were not handed out yet, so it is initialized and not aliased.
+        Some((idx, unsafe { elem.assume_init_mut() }))
+    }
+}
+
+impl<T> RowStorage<T> for PackedStorage<T> {
+    type Iter<'a>
+        = PackedIter<'a, T>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = PackedIterMut<'a, T>
+    where
+        T: 'a;
+    type CursorMut<'a>
+        = SortedCursorMut<PackedIterMut<'a, T>>
+    where
+        T: 'a;
+
+    fn get(&self, idx: usize) -> Option<&T> {
+        // SAFETY: the element is only read if `idx` is in `present`, which marks the initialized elements.
+        self.present
+            .contains(idx)
+            .then(|| unsafe { self.values[idx].assume_init_ref() })
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        // SAFETY: the element is only borrowed if `idx` is in `present`, which marks the initialized elements.
+        self.present
+            .contains(idx)
+            .then(|| unsafe { self.values[idx].assume_init_mut() })
+    }
+
+    fn take(&mut self, idx: usize) -> Option<T> {
+        // SAFETY: the element is only read if `idx` was in `present`, so it is initialized. It is removed from `present` first, so the read value is its only owner and the slot is never read or dropped again.
+        self.present
+            .remove(idx)
+            .then(|| unsafe { self.values[idx].assume_init_read() })
+    }
+
+    fn place(&mut self, idx: usize, new_elem: T) -> Option<T> {
+        if idx >= self.values.len() {
+            self.values.resize_with(idx + 1, MaybeUninit::uninit);
+        }
+        if self.present.insert(idx) {
+            self.values[idx].write(new_elem);
+            None
+        } else {
+            // SAFETY: `insert` returned `false`, i.e., `idx` was already in `present`, so the element is initialized.
+            Some(std::mem::replace(
+                unsafe { self.values[idx].assume_init_mut() },
+                new_elem,
+            ))
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn resize(&mut self, len: usize) {
+        // The present elements are dropped before their slots are truncated, which never drops them. Only the set bits are visited.
+        let mut from = len;
+        while let Some(idx) = self.present.next_set(from) {
+            self.take(idx);
+            from = idx + 1;
+        }
+        self.values.resize_with(len, MaybeUninit::uninit);
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.present.contains(idx)
+    }
+
+    fn count(&self) -> usize {
+        self.present.count()
+    }
+
+    fn next_present(&self, from: usize) -> Option<usize> {
+        self.present.next_set(from)
+    }
+
+    fn next_absent(&self, from: usize) -> usize {
+        self.present.next_unset(from)
+    }
+
+    /// Returns an iterator over the present elements and their indexes, in increasing order of the indexes.
+    fn iter(&self) -> Self::Iter<'_> {
+        PackedIter {
+            values: &self.values,
+            present: self.present.iter(),
+        }
+    }
+
+    /// Returns an iterator over the present elements as mutable references and their indexes, in increasing order of the indexes.
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        PackedIterMut {
+            values: &mut self.values,
+            offset: 0,
+            present: self.present.iter(),
+        }
+    }
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SortedCursorMut::new(self.iter_mut())
+    }
+}
+
+impl<T> PackedStorage<T> {
+    /// Returns an iterator over the maximal runs of present elements as slices, each paired with the index of its first element.
+    pub fn chunks(&self) -> impl Iterator<Item = (usize, &[T])> {
+        let mut rest = self.values.as_slice();
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            let start = self.present.next_set(offset)?;
+            let end = self.present.next_unset(start);
+            let (chunk, tail) = rest[start - offset..].split_at(end - start);
+            rest = tail;
+            offset = end;
+            // SAFETY: the elements of the chunk are present, hence initialized, and `MaybeUninit<T>` has the same layout as `T`.
+            Some((start, unsafe {
+                &*(chunk as *const [MaybeUninit<T>] as *const [T])
+            }))
+        })
+    }
+
+    /// Returns an iterator over the maximal runs of present elements as mutable slices, each paired with the index of its first element.
+    pub fn chunks_mut(&mut self) -> impl Iterator<Item = (usize, &mut [T])> {
+        let present = &self.present;
+        let mut rest = self.values.as_mut_slice();
+        let mut offset = 0;
+        std::iter::from_fn(move || {
+            let start = present.next_set(offset)?;
+            let end = present.next_unset(start);
+            let (chunk, tail) =
+                std::mem::take(&mut rest)[start - offset..].split_at_mut(end - start);
+            rest = tail;
+            offset = end;
+            // SAFETY: the elements of the chunk are present, hence initialized, and `MaybeUninit<T>` has the same layout as `T`.
+            Some((start, unsafe {
+                &mut *(chunk as *mut [MaybeUninit<T>] as *mut [T])
+            }))
+        })
+    }
+}
+
+impl<T> PackedRow<T> {
+    /// Returns an iterator over the maximal runs of present elements as slices, each paired with the index of its first element.
+    pub fn chunks(&self) -> impl Iterator<Item = (usize, &[T])> {
+        self.0.chunks()
+    }
+
+    /// Returns an iterator over the maximal runs of present elements as mutable slices, each paired with the index of its first element.
+    pub fn chunks_mut(&mut self) -> impl Iterator<Item = (usize, &mut [T])> {
+        self.0.chunks_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::*;
+
+    #[test]
+    fn packed_row() {
+        let mut row = PackedRow::<f32>::from_iter([Some(0.5), Some(1.5), None, Some(3.5)]);
+        assert_eq!(row.len(), 4);
+        assert_eq!(row.count(), 3);
+        assert_eq!(row.get(2), None);
+        assert_eq!(row.place(2, 2.5), None);
+        assert_eq!(row.place(6, 6.5), None);
+        assert_eq!(row.take(0), Some(0.5));
+        // The present elements are exposed as contiguous slices.
+        let chunks: Vec<_> = row.chunks().collect();
+        assert_eq!(chunks, vec![(1, &[1.5, 2.5, 3.5][..]), (6, &[6.5][..])]);
+        for (_, chunk) in row.chunks_mut() {
+            chunk.iter_mut().for_each(|val| *val *= 2.0);
+        }
+        for (idx, val) in row.iter_mut() {
+            *val += idx as f32;
+        }
+        assert_eq!(
+            row.iter().collect::<Vec<_>>(),
+            vec![(1, &4.0), (2, &7.0), (3, &10.0), (6, &19.0)]
+        );
+        assert_eq!(
+            row,
+            PackedRow::from_iter([
+                None,
+                Some(4.0),
+                Some(7.0),
+                Some(10.0),
+                None,
+                None,
+                Some(19.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn packed_row_drop() {
+        let rc = Rc::new(());
+        let mut row = PackedRow::from_iter([Some(rc.clone()), None, Some(rc.clone())]);
+        row.place(5, rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 4);
+        // Replacing or taking an element hands the old one back.
+        drop(row.place(0, rc.clone()));
+        drop(row.take(2));
+        assert_eq!(Rc::strong_count(&rc), 3);
+        // Shrinking drops the elements past the new end.
+        row.resize(2);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        row.resize(4);
+        assert_eq!((row.len(), row.count()), (4, 1));
+        let cloned = row.clone();
+        assert_eq!(Rc::strong_count(&rc), 3);
+        // Only the present elements are dropped with the row.
+        drop(row);
+        drop(cloned);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
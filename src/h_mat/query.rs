@@ -0,0 +1,294 @@
+use std::marker::PhantomData;
+
+use crate::{AccessRowRef, Flatten, HMatRef, RowStorage};
+
+/// Represents a type whose columns can be fetched as a whole, i.e., only if all of their elements are present.
+pub trait FetchCol {
+    type Item;
+    /// Returns the elements of the column with the given index `idx` as a recursive pair, e.g., `(&A, (&B, ()))`, or `None` if any of them is missing.
+    fn fetch_col(&self, idx: usize) -> Option<Self::Item>;
+    /// Returns the smallest column index not less than `from` that may be fetched, or `None` if no column can be fetched from `from` on.
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        Some(from)
+    }
+}
+
+/// Marks a row of a query as optional, i.e., the column is fetched as `Option<&T>` whether the element is present or not.
+#[derive(Clone, Copy, Debug)]
+pub struct Opt<T>(PhantomData<*const T>);
+
+/// Marks a row of a query as a filter, i.e., only the columns with a present `T` are fetched, without the `T` itself.
+#[derive(Clone, Copy, Debug)]
+pub struct With<T>(PhantomData<*const T>);
+
+/// Marks a row of a query as an exclusion, i.e., only the columns without a present `T` are fetched.
+#[derive(Clone, Copy, Debug)]
+pub struct Without<T>(PhantomData<*const T>);
+
+/// A recursive description of a query, in which every field is either a row type `T` or a marked row type, e.g., `Opt<T>`, `With<T>` or `Without<T>`.
+#[derive(Clone, Copy, Debug)]
+pub struct HQuery<Q, R>(PhantomData<*const Q>, PhantomData<*const R>);
+
+/// Internal type used for the recursive implementations of the `FetchQuery` trait. `Term` is either `()` for the plain row types, or the marker applied to `()`, e.g., `Opt<()>`, for the marked row types.
+#[derive(Clone, Copy, Debug)]
+pub struct QueryDirective<Term, Head, Tail>(
+    PhantomData<*const Term>,
+    PhantomData<*const Head>,
+    PhantomData<*const Tail>,
+);
+
+/// Represents a type whose columns can be fetched in the shape described by the query `Q`.
+pub trait FetchQuery<'a, Q, Directive> {
+    type Item;
+    /// Returns the fetched elements of the column with the given index `idx` as a recursive pair, or `None` if the column does not match the query.
+    fn fetch_query(&'a self, idx: usize) -> Option<Self::Item>;
+    /// Returns the smallest column index not less than `from` that may match the query, or `None` if no column can match from `from` on.
+    fn next_candidate(&self, from: usize) -> Option<usize>;
+}
+
+/// A reference to `H` that fetches its columns as described by the query `Q`.
+pub struct QueryRef<'a, H, Q, Directive> {
+    pub(crate) mat_ref: &'a H,
+    pub(crate) pd: PhantomData<(*const Q, *const Directive)>,
+}
+
+/// An iterator that iterates over the columns of `H` that can be fetched, yielding them as flat tuples prefixed with the column index, e.g., `(usize, &A, &B)`.
+pub struct QueryIter<H> {
+    pub(crate) mat_ref: H,
+    pub(crate) curr_col_idx: usize,
+    pub(crate) num_cols: usize,
+}
+
+impl<H> FetchCol for &H
+where
+    H: FetchCol,
+{
+    type Item = <H as FetchCol>::Item;
+    fn fetch_col(&self, idx: usize) -> Option<Self::Item> {
+        (**self).fetch_col(idx)
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        (**self).next_candidate(from)
+    }
+}
+
+impl FetchCol for () {
+    type Item = ();
+    fn fetch_col(&self, _idx: usize) -> Option<Self::Item> {
+        Some(())
+    }
+}
+
+impl<'a, T, R, S> FetchCol for HMatRef<'a, T, R, S>
+where
+    S: RowStorage<T>,
+    R: FetchCol,
+{
+    type Item = (&'a T, <R as FetchCol>::Item);
+    fn fetch_col(&self, idx: usize) -> Option<Self::Item> {
+        Some((self.row.get(idx)?, self.rem.fetch_col(idx)?))
+    }
+}
+
+impl<'a, H> FetchQuery<'a, (), ()> for H {
+    type Item = ();
+    fn fetch_query(&'a self, _idx: usize) -> Option<Self::Item> {
+        Some(())
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        Some(from)
+    }
+}
+
+impl<'a, H, D, R, A, Tail> FetchQuery<'a, HQuery<D, R>, QueryDirective<(), A, Tail>> for H
+where
+    D: 'a,
+    H: AccessRowRef<D, A> + FetchQuery<'a, R, Tail>,
+    <H as AccessRowRef<D, A>>::Storage: 'a,
+{
+    type Item = (&'a D, <H as FetchQuery<'a, R, Tail>>::Item);
+    fn fetch_query(&'a self, idx: usize) -> Option<Self::Item> {
+        Some((self.get_row_ref().get(idx)?, self.fetch_query(idx)?))
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        <H as FetchQuery<'a, R, Tail>>::next_candidate(self, from)
+    }
+}
+
+impl<'a, H, D, R, A, Tail> FetchQuery<'a, HQuery<Opt<D>, R>, QueryDirective<Opt<()>, A, Tail>> for H
+where
+    D: 'a,
+    H: AccessRowRef<D, A> + FetchQuery<'a, R, Tail>,
+    <H as AccessRowRef<D, A>>::Storage: 'a,
+{
+    type Item = (Option<&'a D>, <H as FetchQuery<'a, R, Tail>>::Item);
+    fn fetch_query(&'a self, idx: usize) -> Option<Self::Item> {
+        Some((self.get_row_ref().get(idx), self.fetch_query(idx)?))
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        <H as FetchQuery<'a, R, Tail>>::next_candidate(self, from)
+    }
+}
+
+impl<'a, H, D, R, A, Tail> FetchQuery<'a, HQuery<With<D>, R>, QueryDirective<With<()>, A, Tail>>
+    for H
+where
+    H: AccessRowRef<D, A> + FetchQuery<'a, R, Tail>,
+{
+    type Item = <H as FetchQuery<'a, R, Tail>>::Item;
+    fn fetch_query(&'a self, idx: usize) -> Option<Self::Item> {
+        if !self.get_row_ref().contains(idx) {
+            return None;
+        }
+        self.fetch_query(idx)
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        let from = self.get_row_ref().next_present(from)?;
+        <H as FetchQuery<'a, R, Tail>>::next_candidate(self, from)
+    }
+}
+
+impl<'a, H, D, R, A, Tail>
+    FetchQuery<'a, HQuery<Without<D>, R>, QueryDirective<Without<()>, A, Tail>> for H
+where
+    H: AccessRowRef<D, A> + FetchQuery<'a, R, Tail>,
+{
+    type Item = <H as FetchQuery<'a, R, Tail>>::Item;
+    fn fetch_query(&'a self, idx: usize) -> Option<Self::Item> {
+        if self.get_row_ref().contains(idx) {
+            return None;
+        }
+        self.fetch_query(idx)
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        let from = self.get_row_ref().next_absent(from);
+        <H as FetchQuery<'a, R, Tail>>::next_candidate(self, from)
+    }
+}
+
+impl<'a, H, Q, Directive> FetchCol for QueryRef<'a, H, Q, Directive>
+where
+    H: FetchQuery<'a, Q, Directive>,
+{
+    type Item = <H as FetchQuery<'a, Q, Directive>>::Item;
+    fn fetch_col(&self, idx: usize) -> Option<Self::Item> {
+        self.mat_ref.fetch_query(idx)
+    }
+    fn next_candidate(&self, from: usize) -> Option<usize> {
+        self.mat_ref.next_candidate(from)
+    }
+}
+
+impl<H> Iterator for QueryIter<H>
+where
+    H: FetchCol,
+    (usize, <H as FetchCol>::Item): Flatten,
+{
+    type Item = <(usize, <H as FetchCol>::Item) as Flatten>::Flat;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.curr_col_idx < self.num_cols {
+            // The columns that cannot match are skipped, e.g., word by word by the `With` and `Without` filters on bitset storages.
+            let col_idx = match self.mat_ref.next_candidate(self.curr_col_idx) {
+                Some(col_idx) if col_idx < self.num_cols => col_idx,
+                _ => {
+                    self.curr_col_idx = self.num_cols;
+                    break;
+                }
+            };
+            self.curr_col_idx = col_idx + 1;
+            if let Some(item) = self.mat_ref.fetch_col(col_idx) {
+                return Some((col_idx, item).flatten());
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn query() {
+        #[derive(Debug, PartialEq)]
+        struct Position(f32);
+        #[derive(Debug, PartialEq)]
+        struct Velocity(f32);
+        let mat = HMat::<Position, ()>::new_with([
+            Some(Position(0.0)),
+            Some(Position(1.0)),
+            None,
+            Some(Position(3.0)),
+        ])
+        .extend_with::<Velocity, _>([Some(Velocity(0.5)), None, Some(Velocity(2.5))])
+        .extend_with::<i32, _>([None, Some(1), Some(2), Some(3)]);
+        let ref_mat: HMatRef<Position, HMatRef<Velocity, ()>> = mat.slice();
+        // Only the columns with both a `Position` and a `Velocity` are visited.
+        let items: Vec<(usize, &Position, &Velocity)> = ref_mat.query_all().collect();
+        assert_eq!(items, vec![(0, &Position(0.0), &Velocity(0.5))]);
+        let ref_mat: HMatRef<i32, ()> = mat.slice();
+        let items: Vec<(usize, &i32)> = ref_mat.query_all().collect();
+        assert_eq!(items, vec![(1, &1), (2, &2), (3, &3)]);
+    }
+
+    #[test]
+    fn query_markers() {
+        #[derive(Debug, PartialEq)]
+        struct Position(f32);
+        #[derive(Debug, PartialEq)]
+        struct Velocity(f32);
+        #[derive(Debug, PartialEq)]
+        struct Dead;
+        let mat = HMat::<Position, ()>::new_with([
+            Some(Position(0.0)),
+            Some(Position(1.0)),
+            Some(Position(2.0)),
+            Some(Position(3.0)),
+        ])
+        .extend_with::<Velocity, _>([Some(Velocity(0.5)), None, Some(Velocity(2.5))])
+        .extend_with::<Dead, _>([None, None, Some(Dead)])
+        .extend_with::<i32, _>([Some(0), Some(1), Some(2), None]);
+        // Columns that have a `Position` and an `i32`, may have a `Velocity`, and must not be `Dead`.
+        let items: Vec<_> = mat
+            .query::<HQuery<Position, HQuery<Opt<Velocity>, HQuery<Without<Dead>, HQuery<i32, ()>>>>, _>()
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (0, &Position(0.0), Some(&Velocity(0.5)), &0),
+                (1, &Position(1.0), None, &1),
+            ]
+        );
+        // `With` filters the columns without fetching the element.
+        let items: Vec<(usize, &Position)> = mat
+            .query::<HQuery<With<Dead>, HQuery<Position, ()>>, _>()
+            .collect();
+        assert_eq!(items, vec![(2, &Position(2.0))]);
+        // Queries also work on slices.
+        let ref_mat: HMatRef<Velocity, HMatRef<i32, ()>> = mat.slice();
+        let items: Vec<(usize, &i32)> = ref_mat
+            .query::<HQuery<Without<Velocity>, HQuery<i32, ()>>, _>()
+            .collect();
+        assert_eq!(items, vec![(1, &1)]);
+        // The filters on bitset storages skip the columns that cannot match.
+        let mut mat = HMat::<usize, ()>::new_with((0..200).map(Some))
+            .extend_with_storage::<Dead, TagStorage<Dead>>();
+        mat.get_row_mut::<Dead, _>().place(3, Dead);
+        mat.get_row_mut::<Dead, _>().place(150, Dead);
+        let items: Vec<_> = mat
+            .query::<HQuery<With<Dead>, HQuery<usize, ()>>, _>()
+            .collect();
+        assert_eq!(items, vec![(3, &3), (150, &150)]);
+        let count = mat
+            .query::<HQuery<Without<Dead>, HQuery<usize, ()>>, _>()
+            .filter(|(col_idx, _)| *col_idx == 3 || *col_idx == 150)
+            .count();
+        assert_eq!(count, 0);
+        assert_eq!(
+            mat.query::<HQuery<Without<Dead>, HQuery<usize, ()>>, _>()
+                .count(),
+            198
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{AccessRowDirective, HMat, Row};
+
+/// Represents a type that can move one of its rows out, turning into the type without that row.
+pub trait RemoveRow<D, Directive> {
+    /// The storage of the `Row<D>`.
+    type Storage;
+    /// The type without the `Row<D>`.
+    type Rem;
+    /// Removes the `Row<D>` and returns it alongside the remaining rows.
+    fn remove_row(self) -> (Row<D, Self::Storage>, Self::Rem);
+}
+
+impl<D, R, S> RemoveRow<D, ()> for HMat<D, R, S> {
+    type Storage = S;
+    type Rem = R;
+    fn remove_row(self) -> (Row<D, S>, Self::Rem) {
+        (self.head_row, self.rem)
+    }
+}
+
+impl<T, R, S, D, A> RemoveRow<D, AccessRowDirective<A>> for HMat<T, R, S>
+where
+    R: RemoveRow<D, A>,
+{
+    type Storage = <R as RemoveRow<D, A>>::Storage;
+    type Rem = HMat<T, <R as RemoveRow<D, A>>::Rem, S>;
+    fn remove_row(self) -> (Row<D, Self::Storage>, Self::Rem) {
+        let (row, rem) = self.rem.remove_row();
+        (
+            row,
+            HMat {
+                head_row: self.head_row,
+                rem,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn remove_row() {
+        let mat = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with::<f32, _>([Some(0.5)])
+            .extend_with::<i32, _>([None, Some(-1)]);
+        // Remove a row from the middle...
+        let (f32_row, mat) = mat.remove_row::<f32, _>();
+        assert_eq!(f32_row, Row::from_iter([Some(0.5)]));
+        let mat: HMat<i32, HMat<usize, ()>> = mat;
+        // ... or the head row.
+        let (i32_row, mat) = mat.remove_row::<i32, _>();
+        assert_eq!(i32_row, Row::from_iter([None, Some(-1)]));
+        // Removing the last row leaves nothing behind.
+        let (usize_row, ()) = mat.remove_row::<usize, _>();
+        assert_eq!(usize_row, Row::from_iter([Some(0), Some(1)]));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{HMat, RemoveRow, SlicerDirective};
+
+/// Represents a type that can construct itself by moving the rows out of the original type `H`, in any order. The rows that are not moved are dropped.
+pub trait Reshape<H, Directive> {
+    fn reshape(h: H) -> Self;
+}
+
+impl<H, D, S, A> Reshape<H, SlicerDirective<A, ()>> for HMat<D, (), S>
+where
+    H: RemoveRow<D, A, Storage = S>,
+{
+    fn reshape(h: H) -> Self {
+        let (head_row, _) = h.remove_row();
+        HMat { head_row, rem: () }
+    }
+}
+
+impl<H, D1, D2, R, S1, S2, A1, A2, Tail> Reshape<H, SlicerDirective<A1, SlicerDirective<A2, Tail>>>
+    for HMat<D1, HMat<D2, R, S2>, S1>
+where
+    H: RemoveRow<D1, A1, Storage = S1>,
+    HMat<D2, R, S2>: Reshape<<H as RemoveRow<D1, A1>>::Rem, SlicerDirective<A2, Tail>>,
+{
+    fn reshape(h: H) -> Self {
+        let (head_row, rem) = h.remove_row();
+        HMat {
+            head_row,
+            rem: <HMat<D2, R, S2> as Reshape<
+                <H as RemoveRow<D1, A1>>::Rem,
+                SlicerDirective<A2, Tail>,
+            >>::reshape(rem),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn reshape() {
+        let mat = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with::<f32, _>([Some(0.5)])
+            .extend_with::<i32, _>([None, Some(-1)]);
+        // Move the rows into a different order...
+        let mat = mat.reshape::<HMat<f32, HMat<usize, HMat<i32, ()>>>, _>();
+        assert_eq!(mat.get_row_ref::<f32, _>(), &Row::from_iter([Some(0.5)]));
+        let col: HCol<&f32, HCol<&usize, HCol<&i32, ()>>> = mat.get_col_ref(1);
+        assert_eq!(
+            (col.elem, col.rem.elem, col.rem.rem.elem),
+            (None, Some(&1), Some(&-1))
+        );
+        // ... or keep only some of them.
+        let mat: HMat<i32, HMat<f32, ()>> = mat.reshape();
+        assert_eq!(mat.num_cols(), 2);
+    }
+}
@@ -1,46 +1,95 @@
+use std::marker::PhantomData;
+
 use serde::{Deserialize, Serialize};
 
-/// A homogenous row, implemented as a wrapper around `Vec<Option<T>>`.
+use super::RowStorage;
+
+/// A homogenous row, implemented as a wrapper around its storage `S`, which is a `Vec<Option<T>>` by default.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Row<T>(pub(crate) Vec<Option<T>>);
+#[serde(transparent)]
+pub struct Row<T, S = Vec<Option<T>>>(pub(crate) S, #[serde(skip)] pub(crate) PhantomData<T>);
 
-impl<T> Default for Row<T> {
+impl<T, S: Default> Default for Row<T, S> {
     /// Returns an empty row.
     fn default() -> Self {
-        Self(Default::default())
+        Self(Default::default(), PhantomData)
     }
 }
 
-impl<T> Row<T> {
+impl<T, S: RowStorage<T>> Row<T, S> {
     /// Constructs a new row with the given initial elements.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_iter(iter: impl IntoIterator<Item = Option<T>>) -> Self {
-        Self(Vec::from_iter(iter.into_iter()))
+        Self(S::from_iter(iter), PhantomData)
     }
 
     /// Returns the element at the given index.
     pub fn get(&self, idx: usize) -> Option<&T> {
-        self.0.get(idx).map(|opt_elem| opt_elem.as_ref()).flatten()
+        self.0.get(idx)
     }
 
     /// Returns the element at the given index as a mutable reference.
     pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
-        self.0
-            .get_mut(idx)
-            .map(|opt_elem| opt_elem.as_mut())
-            .flatten()
+        self.0.get_mut(idx)
     }
 
     /// Removes the element at the given index and returns it. If an element was indeed removed, the corresponding value at the underlying row will be set to `None`.
     pub fn take(&mut self, idx: usize) -> Option<T> {
-        self.0
-            .get_mut(idx)
-            .map(|opt_elem| opt_elem.take())
-            .flatten()
+        self.0.take(idx)
     }
 
     /// Places a value to the given index. Returns the old value that was at the given index.
     pub fn place(&mut self, idx: usize, new_elem: T) -> Option<T> {
-        self.0.resize_with(idx + 1, || None);
-        self.0.get_mut(idx).unwrap().replace(new_elem)
+        self.0.place(idx, new_elem)
+    }
+
+    /// Returns `true` if there is an element at the given index.
+    pub fn contains(&self, idx: usize) -> bool {
+        self.0.contains(idx)
+    }
+
+    /// Returns the smallest index not less than `from` that has an element.
+    pub fn next_present(&self, from: usize) -> Option<usize> {
+        self.0.next_present(from)
+    }
+
+    /// Returns the smallest index not less than `from` that has no element.
+    pub fn next_absent(&self, from: usize) -> usize {
+        self.0.next_absent(from)
+    }
+
+    /// Returns the length of the underlying row, including the empty elements.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Sets the length of the underlying row, dropping the elements at the indexes from `len` on, or growing it with empty elements.
+    pub fn resize(&mut self, len: usize) {
+        self.0.resize(len)
+    }
+
+    /// Returns `true` if the underlying row has no elements, including the empty ones.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of present elements.
+    pub fn count(&self) -> usize {
+        self.0.count()
+    }
+
+    /// Returns an iterator over the present elements and their indexes. The order depends on the storage.
+    pub fn iter(&self) -> S::Iter<'_> {
+        self.0.iter()
+    }
+
+    /// Returns an iterator over the present elements as mutable references and their indexes. The order depends on the storage.
+    pub fn iter_mut(&mut self) -> S::IterMut<'_> {
+        self.0.iter_mut()
+    }
+
+    /// Returns a reference to the underlying storage.
+    pub fn storage(&self) -> &S {
+        &self.0
     }
 }
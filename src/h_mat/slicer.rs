@@ -11,9 +11,9 @@ pub trait Slicer<'a, H, D, Directive> {
     fn slice(h: &'a H) -> Self;
 }
 
-impl<'a, H, D, A> Slicer<'a, H, D, SlicerDirective<A, ()>> for HMatRef<'a, D, ()>
+impl<'a, H, D, S, A> Slicer<'a, H, D, SlicerDirective<A, ()>> for HMatRef<'a, D, (), S>
 where
-    H: AccessRowRef<D, A>,
+    H: AccessRowRef<D, A, Storage = S>,
 {
     fn slice(h: &'a H) -> Self {
         HMatRef {
@@ -23,18 +23,18 @@ where
     }
 }
 
-impl<'a, H, D1, D2, R, A1, A2, Tail>
+impl<'a, H, D1, D2, R, S1, S2, A1, A2, Tail>
     Slicer<'a, H, D1, SlicerDirective<A1, SlicerDirective<A2, Tail>>>
-    for HMatRef<'a, D1, HMatRef<'a, D2, R>>
+    for HMatRef<'a, D1, HMatRef<'a, D2, R, S2>, S1>
 where
-    H: AccessRowRef<D1, A1>,
-    H: AccessRowRef<D2, A2>,
-    HMatRef<'a, D2, R>: Slicer<'a, H, D2, SlicerDirective<A2, Tail>>,
+    H: AccessRowRef<D1, A1, Storage = S1>,
+    H: AccessRowRef<D2, A2, Storage = S2>,
+    HMatRef<'a, D2, R, S2>: Slicer<'a, H, D2, SlicerDirective<A2, Tail>>,
 {
     fn slice(h: &'a H) -> Self {
         HMatRef {
             row: h.get_row_ref(),
-            rem: <HMatRef<'a, D2, R> as Slicer<'a, H, D2, SlicerDirective<A2, Tail>>>::slice(h),
+            rem: <HMatRef<'a, D2, R, S2> as Slicer<'a, H, D2, SlicerDirective<A2, Tail>>>::slice(h),
         }
     }
 }
@@ -0,0 +1,262 @@
+use crate::{AccessMode, AccessRowDirective, HMat, HMatMut, HMatView, SlicerDirective};
+
+use super::Row;
+
+/// Represents a type whose rows can be borrowed mutably all at once, as a `HMatMut` in the original order.
+pub trait BorrowRowsMut<'a> {
+    type Rows;
+    /// Returns every row of this type as a mutable reference.
+    fn borrow_rows_mut(&'a mut self) -> Self::Rows;
+}
+
+/// Represents a `HMatMut` that can move one of its rows out, returning the rest of the rows alongside it.
+pub trait PluckRowMut<'a, D, Directive> {
+    type Storage;
+    type Rem;
+    /// Moves the `&mut Row<D>` out of this type, returning it with the remaining rows.
+    fn pluck_row_mut(self) -> (&'a mut Row<D, Self::Storage>, Self::Rem);
+}
+
+/// Represents a type that can construct itself from the mutable rows `Rows` by plucking out its fields one by one.
+pub trait SliceRowsMut<'a, Rows, D, Directive> {
+    fn slice_rows_mut(rows: Rows) -> Self;
+}
+
+/// Represents a type that can construct itself by rearranging the fields of the original type `H` as mutable references.
+///
+/// Every row is plucked out of the original matrix before the next one is looked up, so a slice that names the same
+/// row twice does not type-check:
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// let mut mat = HMat::<usize, ()>::new().extend::<f32>();
+/// let _: HMatMut<f32, HMatMut<f32, ()>> = HMatMut::slice_mut(&mut mat);
+/// ```
+pub trait SlicerMut<'a, H, D, Directive> {
+    fn slice_mut(h: &'a mut H) -> Self;
+}
+
+impl<'a, T1, T2, R, S1, S2> BorrowRowsMut<'a> for HMat<T1, HMat<T2, R, S2>, S1>
+where
+    Self: 'a,
+    HMat<T2, R, S2>: BorrowRowsMut<'a>,
+{
+    type Rows = HMatMut<'a, T1, <HMat<T2, R, S2> as BorrowRowsMut<'a>>::Rows, S1>;
+    fn borrow_rows_mut(&'a mut self) -> Self::Rows {
+        HMatMut {
+            row: &mut self.head_row,
+            rem: self.rem.borrow_rows_mut(),
+        }
+    }
+}
+
+impl<'a, T, S> BorrowRowsMut<'a> for HMat<T, (), S>
+where
+    Self: 'a,
+{
+    type Rows = HMatMut<'a, T, (), S>;
+    fn borrow_rows_mut(&'a mut self) -> Self::Rows {
+        HMatMut {
+            row: &mut self.head_row,
+            rem: (),
+        }
+    }
+}
+
+impl<'a, D, R, S> PluckRowMut<'a, D, ()> for HMatMut<'a, D, R, S> {
+    type Storage = S;
+    type Rem = R;
+    fn pluck_row_mut(self) -> (&'a mut Row<D, S>, Self::Rem) {
+        (self.row, self.rem)
+    }
+}
+
+impl<'a, T, R, S, D, A> PluckRowMut<'a, D, AccessRowDirective<A>> for HMatMut<'a, T, R, S>
+where
+    R: PluckRowMut<'a, D, A>,
+{
+    type Storage = <R as PluckRowMut<'a, D, A>>::Storage;
+    type Rem = HMatMut<'a, T, <R as PluckRowMut<'a, D, A>>::Rem, S>;
+    fn pluck_row_mut(self) -> (&'a mut Row<D, Self::Storage>, Self::Rem) {
+        let (row, rem) = self.rem.pluck_row_mut();
+        (row, HMatMut { row: self.row, rem })
+    }
+}
+
+impl<'a, Rows, D, S, A> SliceRowsMut<'a, Rows, D, SlicerDirective<A, ()>> for HMatMut<'a, D, (), S>
+where
+    Rows: PluckRowMut<'a, D, A, Storage = S>,
+{
+    fn slice_rows_mut(rows: Rows) -> Self {
+        let (row, _) = rows.pluck_row_mut();
+        HMatMut { row, rem: () }
+    }
+}
+
+impl<'a, Rows, D1, D2, R, S1, S2, A1, A2, Tail>
+    SliceRowsMut<'a, Rows, D1, SlicerDirective<A1, SlicerDirective<A2, Tail>>>
+    for HMatMut<'a, D1, HMatMut<'a, D2, R, S2>, S1>
+where
+    Rows: PluckRowMut<'a, D1, A1, Storage = S1>,
+    HMatMut<'a, D2, R, S2>:
+        SliceRowsMut<'a, <Rows as PluckRowMut<'a, D1, A1>>::Rem, D2, SlicerDirective<A2, Tail>>,
+{
+    fn slice_rows_mut(rows: Rows) -> Self {
+        let (row, rem_rows) = rows.pluck_row_mut();
+        HMatMut {
+            row,
+            rem: <HMatMut<'a, D2, R, S2> as SliceRowsMut<
+                'a,
+                <Rows as PluckRowMut<'a, D1, A1>>::Rem,
+                D2,
+                SlicerDirective<A2, Tail>,
+            >>::slice_rows_mut(rem_rows),
+        }
+    }
+}
+
+impl<'a, H, D, R, S, Directive> SlicerMut<'a, H, D, Directive> for HMatMut<'a, D, R, S>
+where
+    H: BorrowRowsMut<'a>,
+    Self: SliceRowsMut<'a, <H as BorrowRowsMut<'a>>::Rows, D, Directive>,
+{
+    fn slice_mut(h: &'a mut H) -> Self {
+        Self::slice_rows_mut(h.borrow_rows_mut())
+    }
+}
+
+impl<'a, Rows, M, A> SliceRowsMut<'a, Rows, M, SlicerDirective<A, ()>> for HMatView<'a, M, ()>
+where
+    M: AccessMode<'a>,
+    Rows: PluckRowMut<'a, M::Elem, A, Storage = M::Storage>,
+{
+    fn slice_rows_mut(rows: Rows) -> Self {
+        let (row, _) = rows.pluck_row_mut();
+        HMatView {
+            row: M::from_row_mut(row),
+            rem: (),
+        }
+    }
+}
+
+impl<'a, Rows, M1, M2, R, A1, A2, Tail>
+    SliceRowsMut<'a, Rows, M1, SlicerDirective<A1, SlicerDirective<A2, Tail>>>
+    for HMatView<'a, M1, HMatView<'a, M2, R>>
+where
+    M1: AccessMode<'a>,
+    M2: AccessMode<'a>,
+    Rows: PluckRowMut<'a, M1::Elem, A1, Storage = M1::Storage>,
+    HMatView<'a, M2, R>: SliceRowsMut<
+        'a,
+        <Rows as PluckRowMut<'a, M1::Elem, A1>>::Rem,
+        M2,
+        SlicerDirective<A2, Tail>,
+    >,
+{
+    fn slice_rows_mut(rows: Rows) -> Self {
+        let (row, rem_rows) = rows.pluck_row_mut();
+        HMatView {
+            row: M1::from_row_mut(row),
+            rem: <HMatView<'a, M2, R> as SliceRowsMut<
+                'a,
+                <Rows as PluckRowMut<'a, M1::Elem, A1>>::Rem,
+                M2,
+                SlicerDirective<A2, Tail>,
+            >>::slice_rows_mut(rem_rows),
+        }
+    }
+}
+
+impl<'a, H, M, R, Directive> SlicerMut<'a, H, M, Directive> for HMatView<'a, M, R>
+where
+    M: AccessMode<'a>,
+    H: BorrowRowsMut<'a>,
+    Self: SliceRowsMut<'a, <H as BorrowRowsMut<'a>>::Rows, M, Directive>,
+{
+    fn slice_mut(h: &'a mut H) -> Self {
+        Self::slice_rows_mut(h.borrow_rows_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn slice_mut() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), Some(1), Some(2)])
+            .extend_with::<f32, _>([None, Some(0.5), None])
+            .extend_with::<i32, _>([None, None, Some(-1)]);
+        {
+            // Invoke `slice_mut` to borrow a subset of the rows mutably, with arbitrary row order.
+            let mut mut_mat: HMatMut<f32, HMatMut<i32, ()>> = HMatMut::slice_mut(&mut mat);
+            // The rows can be accessed and modified independently...
+            mut_mat.get_row_mut::<f32, _>().place(0, 1.5);
+            let i32_row: &Row<i32> = mut_mat.get_row_ref();
+            assert_eq!(i32_row, &Row::from_iter([None, None, Some(-1)]));
+            // ... or together as columns.
+            let col = mut_mat.get_col_mut(2);
+            if let Some(val) = col.rem.elem {
+                *val -= 1;
+            }
+            for mut col in mut_mat.iter_mut() {
+                if let Some(val) = col.get_mut::<&mut f32, _>() {
+                    **val *= 2.0;
+                }
+            }
+        }
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(3.0), Some(1.0), None])
+        );
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([None, None, Some(-2)])
+        );
+        {
+            // ... also works as an argument!
+            fn receive_sliced(mut mat: HMatMut<i32, HMatMut<usize, ()>>) {
+                let col: HCol<&mut i32, HCol<&mut usize, ()>> = mat.get_col_mut(0);
+                if let Some(val) = col.rem.elem {
+                    *val = 10;
+                }
+            }
+            receive_sliced(mat.slice_mut());
+        }
+        assert_eq!(
+            mat.get_row_ref::<usize, _>(),
+            &Row::from_iter([Some(10), Some(1), Some(2)])
+        );
+    }
+
+    #[test]
+    fn view() {
+        #[derive(Debug, PartialEq)]
+        struct Position(f32);
+        #[derive(Debug, PartialEq)]
+        struct Velocity(f32);
+        let mut mat = HMat::<Position, ()>::new_with([Some(Position(0.0)), Some(Position(1.0))])
+            .extend_with::<Velocity, _>([Some(Velocity(0.5)), None])
+            .extend::<i32>();
+        {
+            // Read the velocities while writing the positions.
+            let mut view: HMatView<Read<Velocity>, HMatView<Write<Position>, ()>> = mat.view();
+            for col_idx in 0..view.num_cols() {
+                let col: HCol<&Velocity, HCol<&mut Position, ()>> = view.get_col(col_idx);
+                if let (Some(vel), Some(pos)) = (col.elem, col.rem.elem) {
+                    pos.0 += vel.0;
+                }
+            }
+            // Only the `Write` rows can be accessed as mutable references.
+            view.get_row_mut::<Position, _>().take(1);
+            assert_eq!(
+                view.get_row_ref::<Velocity, _>(),
+                &Row::from_iter([Some(Velocity(0.5)), None])
+            );
+        }
+        assert_eq!(
+            mat.get_row_ref::<Position, _>(),
+            &Row::from_iter([Some(Position(0.5)), None])
+        );
+    }
+}
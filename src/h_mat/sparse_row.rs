@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    iter::{Copied, Zip},
+    marker::PhantomData,
+    slice,
+};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Row, RowStorage, SeekMut};
+
+/// A row storage that only stores the present elements, implemented as a dense vector of values and a map from the column indexes to their positions in it.
+/// Suitable for the rows with few present elements, as the memory usage and the iteration cost do not depend on the width of the row.
+#[derive(Clone, Debug)]
+pub struct SparseStorage<T> {
+    pub(crate) values: Vec<T>,
+    pub(crate) indexes: Vec<usize>,
+    pub(crate) positions: HashMap<usize, usize>,
+    pub(crate) len: usize,
+}
+
+/// A homogenous row backed by a `SparseStorage`.
+pub type SparseRow<T> = Row<T, SparseStorage<T>>;
+
+impl<T> Default for SparseStorage<T> {
+    /// Returns an empty storage.
+    fn default() -> Self {
+        Self {
+            values: Default::default(),
+            indexes: Default::default(),
+            positions: Default::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for SparseStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self.values.len() == other.values.len()
+            && self.iter().all(|(idx, elem)| other.get(idx) == Some(elem))
+    }
+}
+
+impl<T: Eq> Eq for SparseStorage<T> {}
+
+/// The serialized form of a `SparseStorage`, whose position map is rebuilt on deserialization.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "SparseStorage")]
+struct SparseData<V, I> {
+    values: V,
+    indexes: I,
+    len: usize,
+}
+
+impl<T: Serialize> Serialize for SparseStorage<T> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        SparseData {
+            values: &self.values,
+            indexes: &self.indexes,
+            len: self.len,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SparseStorage<T> {
+    /// Deserializes the storage, rejecting the data with duplicate or out of bounds indexes, or with different numbers of values and indexes.
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let data = SparseData::<Vec<T>, Vec<usize>>::deserialize(deserializer)?;
+        if data.values.len() != data.indexes.len() {
+            return Err(De::Error::custom(
+                "the numbers of values and indexes differ",
+            ));
+        }
+        let mut positions = HashMap::with_capacity(data.indexes.len());
+        for (pos, &idx) in data.indexes.iter().enumerate() {
+            if idx >= data.len || positions.insert(idx, pos).is_some() {
+                return Err(De::Error::custom(format!("invalid index {idx}")));
+            }
+        }
+        Ok(Self {
+            values: data.values,
+            indexes: data.indexes,
+            positions,
+            len: data.len,
+        })
+    }
+}
+
+impl<T> FromIterator<Option<T>> for SparseStorage<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut storage = Self::default();
+        for (idx, opt_elem) in iter.into_iter().enumerate() {
+            if let Some(elem) = opt_elem {
+                storage.place(idx, elem);
+            }
+            storage.len = idx + 1;
+        }
+        storage
+    }
+}
+
+/// A cursor over the elements of a `SparseStorage`, which looks the sought indexes up in the position map.
+pub struct SparseCursorMut<'a, T> {
+    pub(crate) values: *mut T,
+    pub(crate) positions: &'a HashMap<usize, usize>,
+    /// The smallest index that can be sought next.
+    pub(crate) next_idx: usize,
+    pub(crate) pd: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> SeekMut for SparseCursorMut<'a, T> {
+    type Item = &'a mut T;
+    fn seek_mut(&mut self, idx: usize) -> Option<&'a mut T> {
+        if idx < self.next_idx {
+            return None;
+        }
+        self.next_idx = idx + 1;
+        let &pos = self.positions.get(&idx)?;
+        // SAFETY: `values` points to the values of a storage that is mutably borrowed for `'a`, and `pos` is in its bounds.
+        // The positions of distinct indexes are distinct, and every index is sought at most once, so the returned references never alias.
+        Some(unsafe { &mut *self.values.add(pos) })
+    }
+}
+
+impl<T> RowStorage<T> for SparseStorage<T> {
+    type Iter<'a>
+        = Zip<Copied<slice::Iter<'a, usize>>, slice::Iter<'a, T>>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = Zip<Copied<slice::Iter<'a, usize>>, slice::IterMut<'a, T>>
+    where
+        T: 'a;
+    type CursorMut<'a>
+        = SparseCursorMut<'a, T>
+    where
+        T: 'a;
+
+    fn get(&self, idx: usize) -> Option<&T> {
+        self.positions.get(&idx).map(|&pos| &self.values[pos])
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.positions.get(&idx).map(|&pos| &mut self.values[pos])
+    }
+
+    /// Removes the element at the given index and returns it. The last present element is moved to the freed position of the dense vector.
+    fn take(&mut self, idx: usize) -> Option<T> {
+        let pos = self.positions.remove(&idx)?;
+        self.indexes.swap_remove(pos);
+        let elem = self.values.swap_remove(pos);
+        if let Some(&moved_idx) = self.indexes.get(pos) {
+            self.positions.insert(moved_idx, pos);
+        }
+        Some(elem)
+    }
+
+    fn place(&mut self, idx: usize, new_elem: T) -> Option<T> {
+        self.len = self.len.max(idx + 1);
+        if let Some(&pos) = self.positions.get(&idx) {
+            return Some(std::mem::replace(&mut self.values[pos], new_elem));
+        }
+        self.positions.insert(idx, self.values.len());
+        self.indexes.push(idx);
+        self.values.push(new_elem);
+        None
+    }
+
+    /// Returns the length of the storage, i.e., one more than the largest index an element was placed to.
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn resize(&mut self, len: usize) {
+        // Only the present elements are visited. `take` moves the last element to `pos`, so `pos` is checked again.
+        let mut pos = 0;
+        while let Some(&idx) = self.indexes.get(pos) {
+            if idx < len {
+                pos += 1;
+            } else {
+                self.take(idx);
+            }
+        }
+        self.len = len;
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.positions.contains_key(&idx)
+    }
+
+    fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns an iterator over the present elements and their indexes, in no particular order.
+    fn iter(&self) -> Self::Iter<'_> {
+        self.indexes.iter().copied().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over the present elements as mutable references and their indexes, in no particular order.
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        self.indexes.iter().copied().zip(self.values.iter_mut())
+    }
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SparseCursorMut {
+            values: self.values.as_mut_ptr(),
+            positions: &self.positions,
+            next_idx: 0,
+            pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn sparse_row() {
+        let mut row = SparseRow::<i32>::from_iter([Some(1), None, Some(3)]);
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.count(), 2);
+        assert_eq!(row.get(0), Some(&1));
+        assert_eq!(row.get(1), None);
+        assert_eq!(row.place(1_000_000, 5), None);
+        assert_eq!(row.place(2, 4), Some(3));
+        assert_eq!(row.len(), 1_000_001);
+        if let Some(val) = row.get_mut(1_000_000) {
+            *val += 1;
+        }
+        // Taking an element moves the last one into its position.
+        assert_eq!(row.take(0), Some(1));
+        assert_eq!(row.take(0), None);
+        assert_eq!(row.get(1_000_000), Some(&6));
+        assert_eq!(row.get(2), Some(&4));
+        // Only the present elements are visited.
+        let mut elems: Vec<_> = row.iter().collect();
+        elems.sort();
+        assert_eq!(elems, vec![(2, &4), (1_000_000, &6)]);
+        for (_, val) in row.iter_mut() {
+            *val *= 10;
+        }
+        let mut other = SparseRow::default();
+        other.place(1_000_000, 60);
+        other.place(2, 40);
+        assert_eq!(row, other);
+        // The storage survives a round trip, while the inconsistent data is rejected.
+        let json = serde_json::to_string(&row).unwrap();
+        assert_eq!(serde_json::from_str::<SparseRow<i32>>(&json).unwrap(), row);
+        for json in [
+            r#"{"values":[1],"indexes":[5000],"len":1}"#,
+            r#"{"values":[1,2],"indexes":[0,0],"len":1}"#,
+            r#"{"values":[1,2],"indexes":[0],"len":1}"#,
+        ] {
+            assert!(serde_json::from_str::<SparseRow<i32>>(json).is_err());
+        }
+        // Shrinking drops the elements past the new length, and keeps the positions of the rest.
+        row.place(0, 10);
+        row.resize(3);
+        assert_eq!(row.len(), 3);
+        assert_eq!(row.count(), 2);
+        assert_eq!(row.get(2), Some(&40));
+        assert_eq!(row.take(0), Some(10));
+        assert_eq!(row.get(2), Some(&40));
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{HMat, RemoveRow, SlicerDirective};
+
+/// Represents a type that can construct itself by moving the rows out of the original type `H`, in any order, returning the remaining rows alongside it.
+pub trait Split<H, Directive>: Sized {
+    /// The type of the remaining rows.
+    type Rem;
+    fn split(h: H) -> (Self, Self::Rem);
+}
+
+impl<H, D, S, A> Split<H, SlicerDirective<A, ()>> for HMat<D, (), S>
+where
+    H: RemoveRow<D, A, Storage = S>,
+{
+    type Rem = <H as RemoveRow<D, A>>::Rem;
+    fn split(h: H) -> (Self, Self::Rem) {
+        let (head_row, rem) = h.remove_row();
+        let mat = HMat { head_row, rem: () };
+        (mat, rem)
+    }
+}
+
+impl<H, D1, D2, R, S1, S2, A1, A2, Tail> Split<H, SlicerDirective<A1, SlicerDirective<A2, Tail>>>
+    for HMat<D1, HMat<D2, R, S2>, S1>
+where
+    H: RemoveRow<D1, A1, Storage = S1>,
+    HMat<D2, R, S2>: Split<<H as RemoveRow<D1, A1>>::Rem, SlicerDirective<A2, Tail>>,
+{
+    type Rem =
+        <HMat<D2, R, S2> as Split<<H as RemoveRow<D1, A1>>::Rem, SlicerDirective<A2, Tail>>>::Rem;
+    fn split(h: H) -> (Self, Self::Rem) {
+        let (head_row, rem_rows) = h.remove_row();
+        let (rem, rest) = <HMat<D2, R, S2> as Split<
+            <H as RemoveRow<D1, A1>>::Rem,
+            SlicerDirective<A2, Tail>,
+        >>::split(rem_rows);
+        let mat = HMat { head_row, rem };
+        (mat, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn split() {
+        let world = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with::<f32, _>([Some(0.5)])
+            .extend_with::<i32, _>([None, Some(-1)])
+            .extend_with::<char, _>([Some('a')]);
+        // Move the i32 and usize rows out, keeping the rest.
+        let (part, rest): (HMat<i32, HMat<usize, ()>>, _) = world.split();
+        let rest: HMat<char, HMat<f32, ()>> = rest;
+        assert_eq!(
+            part.get_row_ref::<i32, _>(),
+            &Row::from_iter([None, Some(-1)])
+        );
+        assert_eq!(rest.get_row_ref::<f32, _>(), &Row::from_iter([Some(0.5)]));
+        // Both halves can be joined back together.
+        let world = rest.append(part);
+        assert_eq!(world.get_col_ref(1).get::<&i32, _>(), Some(&&-1));
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    iter::{Enumerate, Peekable},
+    slice,
+};
+
+/// Represents the storage backend of a `Row<T>`, i.e., a mapping from the column indexes to the present elements.
+pub trait RowStorage<T>: Default + FromIterator<Option<T>> {
+    /// The iterator returned by `iter`.
+    type Iter<'a>: Iterator<Item = (usize, &'a T)>
+    where
+        Self: 'a,
+        T: 'a;
+    /// The iterator returned by `iter_mut`.
+    type IterMut<'a>: Iterator<Item = (usize, &'a mut T)>
+    where
+        Self: 'a,
+        T: 'a;
+    /// The cursor returned by `cursor_mut`.
+    type CursorMut<'a>: SeekMut<Item = &'a mut T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    /// Returns the element at the given index.
+    fn get(&self, idx: usize) -> Option<&T>;
+    /// Returns the element at the given index as a mutable reference.
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T>;
+    /// Removes the element at the given index and returns it.
+    fn take(&mut self, idx: usize) -> Option<T>;
+    /// Places a value to the given index. Returns the old value that was at the given index.
+    fn place(&mut self, idx: usize, new_elem: T) -> Option<T>;
+    /// Returns the length of the storage, i.e., one more than the largest index it can hold an element at without growing.
+    fn len(&self) -> usize;
+    /// Sets the length of the storage, dropping the elements at the indexes from `len` on, or growing it with absent elements.
+    fn resize(&mut self, len: usize);
+    /// Returns an iterator over the present elements and their indexes.
+    fn iter(&self) -> Self::Iter<'_>;
+    /// Returns an iterator over the present elements as mutable references and their indexes.
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+    /// Returns a cursor that yields the elements as mutable references by their indexes, in increasing order of the indexes.
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_>;
+
+    /// Returns `true` if the storage has a length of zero.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if there is an element at the given index.
+    fn contains(&self, idx: usize) -> bool {
+        self.get(idx).is_some()
+    }
+
+    /// Returns the number of present elements.
+    fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns the smallest index not less than `from` that has an element.
+    fn next_present(&self, from: usize) -> Option<usize> {
+        (from..self.len()).find(|&idx| self.contains(idx))
+    }
+
+    /// Returns the smallest index not less than `from` that has no element.
+    fn next_absent(&self, from: usize) -> usize {
+        (from..self.len())
+            .find(|&idx| !self.contains(idx))
+            .unwrap_or(self.len().max(from))
+    }
+}
+
+/// Represents a cursor that moves forward over the elements of a row.
+pub trait SeekMut {
+    type Item;
+    /// Moves the cursor past the given index and returns the element at it. Returns `None` for the indexes that are not larger than the previously sought ones.
+    fn seek_mut(&mut self, idx: usize) -> Option<Self::Item>;
+}
+
+/// A cursor over an iterator that yields the present elements in increasing order of their indexes.
+pub struct SortedCursorMut<I: Iterator>(pub(crate) Peekable<I>);
+
+impl<I: Iterator> SortedCursorMut<I> {
+    /// Creates a cursor over the given iterator, which must yield the elements in increasing order of their indexes.
+    pub fn new(iter: I) -> Self {
+        SortedCursorMut(iter.peekable())
+    }
+}
+
+impl<'a, T: 'a, I> SeekMut for SortedCursorMut<I>
+where
+    I: Iterator<Item = (usize, &'a mut T)>,
+{
+    type Item = &'a mut T;
+    fn seek_mut(&mut self, idx: usize) -> Option<&'a mut T> {
+        while self.0.next_if(|(elem_idx, _)| *elem_idx < idx).is_some() {}
+        self.0
+            .next_if(|(elem_idx, _)| *elem_idx == idx)
+            .map(|(_, elem)| elem)
+    }
+}
+
+/// An iterator over the present elements of a `Vec<Option<T>>` and their indexes.
+pub struct VecIter<'a, T>(pub(crate) Enumerate<slice::Iter<'a, Option<T>>>);
+
+impl<'a, T> Iterator for VecIter<'a, T> {
+    type Item = (usize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .find_map(|(idx, opt_elem)| Some((idx, opt_elem.as_ref()?)))
+    }
+}
+
+/// An iterator over the present elements of a `Vec<Option<T>>` as mutable references and their indexes.
+pub struct VecIterMut<'a, T>(pub(crate) Enumerate<slice::IterMut<'a, Option<T>>>);
+
+impl<'a, T> Iterator for VecIterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .find_map(|(idx, opt_elem)| Some((idx, opt_elem.as_mut()?)))
+    }
+}
+
+impl<T> RowStorage<T> for Vec<Option<T>> {
+    type Iter<'a>
+        = VecIter<'a, T>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = VecIterMut<'a, T>
+    where
+        T: 'a;
+    type CursorMut<'a>
+        = SortedCursorMut<VecIterMut<'a, T>>
+    where
+        T: 'a;
+
+    fn get(&self, idx: usize) -> Option<&T> {
+        self.as_slice()
+            .get(idx)
+            .and_then(|opt_elem| opt_elem.as_ref())
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.as_mut_slice()
+            .get_mut(idx)
+            .and_then(|opt_elem| opt_elem.as_mut())
+    }
+
+    fn take(&mut self, idx: usize) -> Option<T> {
+        self.as_mut_slice()
+            .get_mut(idx)
+            .and_then(|opt_elem| opt_elem.take())
+    }
+
+    fn place(&mut self, idx: usize, new_elem: T) -> Option<T> {
+        if idx >= Vec::len(self) {
+            self.resize_with(idx + 1, || None);
+        }
+        self[idx].replace(new_elem)
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn resize(&mut self, len: usize) {
+        self.resize_with(len, || None);
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        VecIter(self.as_slice().iter().enumerate())
+    }
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        VecIterMut(self.as_mut_slice().iter_mut().enumerate())
+    }
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SortedCursorMut::new(RowStorage::iter_mut(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn storage() {
+        let mut mat = HMat::<usize, ()>::new_with([Some(0), Some(1)])
+            .extend_with_storage::<i32, SparseStorage<i32>>()
+            .extend_with::<f32, _>([Some(0.5)]);
+        // The sparse row is accessed like any other row.
+        mat.get_row_mut::<i32, _>().place(1_000, -1);
+        mat.get_row_mut::<i32, _>().place(1, -2);
+        assert_eq!(mat.num_cols(), 1_001);
+        let col = mat.get_col_ref(1_000);
+        assert_eq!(
+            (col.elem, col.rem.elem, col.rem.rem.elem),
+            (None, Some(&-1), None)
+        );
+        // Slices name the storage of their rows.
+        let ref_mat: hmat_ref_type![i32: SparseStorage<i32>, usize] = mat.slice();
+        let items: Vec<_> = ref_mat.query_all().collect();
+        assert_eq!(items, vec![(1, &-2, &1)]);
+        // Writers and mutable iterators work regardless of the storage.
+        let mut writer = mat.new_writer();
+        writer.update_col(1_000, |val: &mut i32| *val *= 10);
+        writer.set_col(1_000, 5);
+        mat.apply(writer);
+        for mut col in mat.iter_mut() {
+            if let Some(val) = col.get_mut::<&mut i32, _>() {
+                **val += 1;
+            }
+        }
+        let i32_row: &SparseRow<i32> = mat.get_row_ref();
+        assert_eq!(i32_row.count(), 2);
+        assert_eq!(
+            mat.take_col(1_000).rem,
+            HCol {
+                elem: Some(51),
+                rem: HCol {
+                    elem: None,
+                    rem: ()
+                }
+            }
+        );
+    }
+}
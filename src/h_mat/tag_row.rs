@@ -0,0 +1,316 @@
+use std::{fmt, marker::PhantomData, ptr::NonNull};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{BitSet, BitSetIter, Row, RowStorage, SortedCursorMut};
+
+/// A row storage for the zero-sized types, e.g., marker components, implemented as a bitset that marks the present elements.
+/// The storage cannot be picked by the size of the type on stable Rust, so a row opts into it, e.g., `hmat_type![Dead: TagStorage<Dead>, usize]`.
+/// Using it with a type that is not zero-sized fails to compile:
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// let mat = HMat::<usize, ()>::new().extend_with_storage::<u8, TagStorage<u8>>();
+/// ```
+pub struct TagStorage<T> {
+    pub(crate) present: BitSet,
+    pub(crate) len: usize,
+    pub(crate) pd: PhantomData<T>,
+}
+
+/// A homogenous row of a zero-sized type backed by a `TagStorage`.
+pub type TagRow<T> = Row<T, TagStorage<T>>;
+
+impl<T> TagStorage<T> {
+    /// Evaluated whenever a storage is created, so that it cannot be used with a type that is not zero-sized.
+    const ZERO_SIZED: () = assert!(
+        std::mem::size_of::<T>() == 0,
+        "TagStorage can only store zero-sized types"
+    );
+
+    /// Returns a reference to an element, which needs no memory as `T` is zero-sized.
+    fn elem_ref<'a>() -> &'a T {
+        // SAFETY: `T` is zero-sized, as `ZERO_SIZED` is checked whenever a storage is created, and any aligned, non-null pointer is valid for the reads of a zero-sized type.
+        unsafe { NonNull::dangling().as_ref() }
+    }
+
+    /// Returns a mutable reference to an element, which needs no memory as `T` is zero-sized.
+    fn elem_mut<'a>() -> &'a mut T {
+        // SAFETY: `T` is zero-sized, as `ZERO_SIZED` is checked whenever a storage is created, and any aligned, non-null pointer is valid for the reads and writes of a zero-sized type.
+        unsafe { NonNull::dangling().as_mut() }
+    }
+
+    /// Returns the bitset that marks the present elements.
+    pub fn bits(&self) -> &BitSet {
+        &self.present
+    }
+}
+
+impl<T> Default for TagStorage<T> {
+    /// Returns an empty storage.
+    fn default() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::ZERO_SIZED;
+        Self {
+            present: Default::default(),
+            len: 0,
+            pd: PhantomData,
+        }
+    }
+}
+
+impl<T> Drop for TagStorage<T> {
+    /// Drops the present elements, as they were moved into the storage.
+    fn drop(&mut self) {
+        if std::mem::needs_drop::<T>() {
+            for _ in self.present.iter() {
+                // SAFETY: `T` is zero-sized, so the dangling pointer is valid for it. Every index in `present` stands for an element that was moved into the storage and forgotten, and it is dropped exactly once here.
+                unsafe { NonNull::<T>::dangling().as_ptr().drop_in_place() };
+            }
+        }
+    }
+}
+
+impl<T: Clone> Clone for TagStorage<T> {
+    fn clone(&self) -> Self {
+        (0..self.len).map(|idx| self.get(idx).cloned()).collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TagStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len).map(|idx| self.get(idx)))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for TagStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.present == other.present
+    }
+}
+
+impl<T> Eq for TagStorage<T> {}
+
+impl<T: Serialize> Serialize for TagStorage<T> {
+    /// Serializes the storage in the same format as a `Vec<Option<T>>`.
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_seq((0..self.len).map(|idx| self.get(idx)))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for TagStorage<T> {
+    /// Deserializes the storage from the same format as a `Vec<Option<T>>`.
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        Ok(Vec::<Option<T>>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+impl<T> FromIterator<Option<T>> for TagStorage<T> {
+    fn from_iter<I: IntoIterator<Item = Option<T>>>(iter: I) -> Self {
+        let mut storage = Self::default();
+        for (idx, opt_elem) in iter.into_iter().enumerate() {
+            if let Some(elem) = opt_elem {
+                storage.place(idx, elem);
+            }
+            storage.len = idx + 1;
+        }
+        storage
+    }
+}
+
+/// An iterator over the present elements of a `TagStorage` and their indexes.
+pub struct TagIter<'a, T> {
+    pub(crate) present: BitSetIter<'a>,
+    pub(crate) pd: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for TagIter<'a, T> {
+    type Item = (usize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.present.next()?, TagStorage::elem_ref()))
+    }
+}
+
+/// An iterator over the present elements of a `TagStorage` as mutable references and their indexes.
+pub struct TagIterMut<'a, T> {
+    pub(crate) present: BitSetIter<'a>,
+    pub(crate) pd: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for TagIterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.present.next()?, TagStorage::elem_mut()))
+    }
+}
+
+impl<T> RowStorage<T> for TagStorage<T> {
+    type Iter<'a>
+        = TagIter<'a, T>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = TagIterMut<'a, T>
+    where
+        T: 'a;
+    type CursorMut<'a>
+        = SortedCursorMut<TagIterMut<'a, T>>
+    where
+        T: 'a;
+
+    fn get(&self, idx: usize) -> Option<&T> {
+        self.present.contains(idx).then(Self::elem_ref)
+    }
+
+    fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.present.contains(idx).then(Self::elem_mut)
+    }
+
+    fn take(&mut self, idx: usize) -> Option<T> {
+        // SAFETY: `T` is zero-sized, so the dangling pointer is valid for reading it. The element is only read if `idx` was in `present`, i.e., an element was moved into the storage and forgotten, and `idx` is removed first, so it is moved back out exactly once.
+        self.present
+            .remove(idx)
+            .then(|| unsafe { NonNull::<T>::dangling().as_ptr().read() })
+    }
+
+    fn place(&mut self, idx: usize, new_elem: T) -> Option<T> {
+        self.len = self.len.max(idx + 1);
+        // The new element is kept alive by the bit, and dropped when it is taken out or the storage is dropped.
+        std::mem::forget(new_elem);
+        if self.present.insert(idx) {
+            None
+        } else {
+            // SAFETY: `T` is zero-sized, so the dangling pointer is valid for reading it. `insert` returned `false`, i.e., the old element was moved into the storage and forgotten, and the bit now stands for the new one, so the old one is moved out exactly once.
+            Some(unsafe { NonNull::<T>::dangling().as_ptr().read() })
+        }
+    }
+
+    /// Returns the length of the storage, i.e., one more than the largest index an element was placed to.
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn resize(&mut self, len: usize) {
+        // Only the set bits are visited, so that the dropped elements are found word by word.
+        let mut from = len;
+        while let Some(idx) = self.present.next_set(from) {
+            self.take(idx);
+            from = idx + 1;
+        }
+        self.len = len;
+    }
+
+    fn contains(&self, idx: usize) -> bool {
+        self.present.contains(idx)
+    }
+
+    fn count(&self) -> usize {
+        self.present.count()
+    }
+
+    fn next_present(&self, from: usize) -> Option<usize> {
+        self.present.next_set(from)
+    }
+
+    fn next_absent(&self, from: usize) -> usize {
+        self.present.next_unset(from)
+    }
+
+    /// Returns an iterator over the present elements and their indexes, in increasing order of the indexes.
+    fn iter(&self) -> Self::Iter<'_> {
+        TagIter {
+            present: self.present.iter(),
+            pd: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the present elements as mutable references and their indexes, in increasing order of the indexes.
+    fn iter_mut(&mut self) -> Self::IterMut<'_> {
+        TagIterMut {
+            present: self.present.iter(),
+            pd: PhantomData,
+        }
+    }
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SortedCursorMut::new(self.iter_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::*;
+
+    #[test]
+    fn tag_row() {
+        #[derive(Debug, PartialEq)]
+        struct Position(f32);
+        #[derive(Debug, PartialEq)]
+        struct Dead;
+        let mut mat = HMat::<Position, ()>::new_with([
+            Some(Position(0.0)),
+            Some(Position(1.0)),
+            Some(Position(2.0)),
+        ])
+        .extend_with_storage::<Dead, TagStorage<Dead>>();
+        mat.get_row_mut::<Dead, _>().place(1, Dead);
+        mat.get_row_mut::<Dead, _>().place(200, Dead);
+        // The tags are accessed like any other element.
+        let dead_row: &TagRow<Dead> = mat.get_row_ref();
+        assert_eq!(dead_row.get(1), Some(&Dead));
+        assert_eq!(dead_row.count(), 2);
+        assert_eq!(mat.get_col_ref(200).get::<&Dead, _>(), Some(&&Dead));
+        // `With` and `Without` only look at the bits.
+        let items: Vec<_> = mat
+            .query::<HQuery<With<Dead>, HQuery<Position, ()>>, _>()
+            .collect();
+        assert_eq!(items, vec![(1, &Position(1.0))]);
+        let items: Vec<_> = mat
+            .query::<HQuery<Without<Dead>, HQuery<Position, ()>>, _>()
+            .map(|(col_idx, _)| col_idx)
+            .collect();
+        assert_eq!(items, vec![0, 2]);
+        assert_eq!(mat.take_col(1).elem, Some(Dead));
+        assert!(!mat.get_row_ref::<Dead, _>().contains(1));
+    }
+
+    #[test]
+    fn tag_row_drop() {
+        thread_local!(static DROPS: Cell<usize> = const { Cell::new(0) });
+        #[derive(Clone)]
+        struct Counted;
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.with(|drops| drops.set(drops.get() + 1));
+            }
+        }
+        // The elements are dropped exactly once, when they are taken out or with the row.
+        let mut row = TagRow::from_iter([Some(Counted), None, Some(Counted)]);
+        row.place(5, Counted);
+        // Replacing an element hands the old one back.
+        let old = row.place(5, Counted);
+        assert!(old.is_some());
+        assert_eq!(DROPS.with(Cell::get), 0);
+        drop(old);
+        assert_eq!(DROPS.with(Cell::get), 1);
+        drop(row.take(0));
+        assert_eq!(DROPS.with(Cell::get), 2);
+        // Shrinking drops the elements past the new length, also in the later words.
+        row.place(200, Counted);
+        row.resize(3);
+        assert_eq!(DROPS.with(Cell::get), 4);
+        assert_eq!(row.len(), 3);
+        assert!(row.contains(2));
+        let cloned = row.clone();
+        drop(row);
+        assert_eq!(DROPS.with(Cell::get), 5);
+        drop(cloned);
+        assert_eq!(DROPS.with(Cell::get), 6);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::HCol;
+
+/// Represents a recursive pair, e.g., `(A, (B, (C, ())))`, that can be flattened into a tuple, e.g., `(A, B, C)`.
+pub trait Flatten {
+    type Flat;
+    /// Flattens this recursive pair into a tuple.
+    fn flatten(self) -> Self::Flat;
+}
+
+/// Expands to the recursive pair type or pattern of the given identifiers, e.g., `(A, (B, ()))` for `A, B`.
+macro_rules! nested {
+    () => { () };
+    ($head:ident $(, $tail:ident)*) => { ($head, nested!($($tail),*)) };
+}
+
+/// Implements `Flatten` for the recursive pairs of every length up to the number of given identifiers.
+macro_rules! impl_flatten {
+    () => {};
+    ($head:ident $(, $tail:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<$head $(, $tail)*> Flatten for nested!($head $(, $tail)*) {
+            type Flat = ($head, $($tail,)*);
+            fn flatten(self) -> Self::Flat {
+                let nested!($head $(, $tail)*) = self;
+                ($head, $($tail,)*)
+            }
+        }
+        impl_flatten!($($tail),*);
+    };
+}
+
+impl_flatten!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q);
+
+/// Expands to the `HCol` type of the given identifiers, e.g., `HCol<A, HCol<B, ()>>` for `A, B`.
+macro_rules! nested_col_type {
+    () => { () };
+    ($head:ident $(, $tail:ident)*) => { HCol<$head, nested_col_type!($($tail),*)> };
+}
+
+/// Expands to the `HCol` pattern or expression of the given identifiers, e.g., `HCol { elem: A, rem: HCol { elem: B, rem: () } }` for `A, B`.
+macro_rules! nested_col {
+    () => { () };
+    ($head:ident $(, $tail:ident)*) => { HCol { elem: $head, rem: nested_col!($($tail),*) } };
+}
+
+/// Implements `Flatten` and the conversions from and into the tuples of `Option`s for the columns of every length up to the number of given identifiers.
+macro_rules! impl_col_tuple {
+    () => {};
+    ($head:ident $(, $tail:ident)*) => {
+        #[allow(non_snake_case)]
+        impl<$head $(, $tail)*> Flatten for nested_col_type!($head $(, $tail)*) {
+            type Flat = (Option<$head>, $(Option<$tail>,)*);
+            fn flatten(self) -> Self::Flat {
+                let nested_col!($head $(, $tail)*) = self;
+                ($head, $($tail,)*)
+            }
+        }
+        #[allow(non_snake_case)]
+        impl<$head $(, $tail)*> From<(Option<$head>, $(Option<$tail>,)*)> for nested_col_type!($head $(, $tail)*) {
+            fn from(tuple: (Option<$head>, $(Option<$tail>,)*)) -> Self {
+                let ($head, $($tail,)*) = tuple;
+                nested_col!($head $(, $tail)*)
+            }
+        }
+        impl<$head $(, $tail)*> From<nested_col_type!($head $(, $tail)*)> for (Option<$head>, $(Option<$tail>,)*) {
+            fn from(col: nested_col_type!($head $(, $tail)*)) -> Self {
+                col.flatten()
+            }
+        }
+        impl_col_tuple!($($tail),*);
+    };
+}
+
+impl_col_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn flatten() {
+        assert_eq!((0, ()).flatten(), (0,));
+        assert_eq!((0, ("a", (0.5, ()))).flatten(), (0, "a", 0.5));
+    }
+
+    #[test]
+    fn col_tuple() {
+        let mut mat = HMat::<usize, ()>::new().extend::<f32>().extend::<i32>();
+        mat.place_col(1, (Some(1), None, Some(2)).into());
+        let col = mat.take_col(1);
+        assert_eq!(
+            col,
+            HCol::new(Some(1), HCol::new(None, HCol::new(Some(2), ())))
+        );
+        assert_eq!(col.clone().into_tuple(), (Some(1), None, Some(2)));
+        let (i32_elem, rem) = col.into_inner();
+        assert_eq!(i32_elem, Some(1));
+        let tuple: (Option<f32>, Option<usize>) = rem.into();
+        assert_eq!(tuple, (None, Some(2)));
+    }
+}
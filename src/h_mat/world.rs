@@ -0,0 +1,231 @@
+use std::ops::{Deref, Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AccessColMut, AccessColRef, AppendCols, Entity, EntityAllocator, HCol, HMat, NumCols, PlaceCol,
+    Split, TakeCol,
+};
+
+/// A matrix `M` together with the `EntityAllocator` that keeps track of its columns. Dereferences to the matrix for reading only, so that its columns are only moved through the methods that keep the entities in sync:
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// let mut world = World::new(HMat::<usize, ()>::new());
+/// world.spawn();
+/// world.take_col(0);
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct World<M> {
+    pub(crate) mat: M,
+    pub(crate) entities: EntityAllocator,
+}
+
+impl<M> World<M> {
+    /// Creates a new `World` around the given matrix. Its existing columns are never handed out by `spawn`.
+    pub fn new(mat: M) -> Self {
+        World {
+            mat,
+            entities: Default::default(),
+        }
+    }
+
+    /// Returns the matrix, dropping the entities.
+    pub fn into_inner(self) -> M {
+        self.mat
+    }
+
+    /// Returns the allocator that keeps track of the entities of this world.
+    pub fn entities(&self) -> &EntityAllocator {
+        &self.entities
+    }
+
+    /// Returns `true` if the given entity is alive.
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// Returns the alive entity whose column index is `col_idx`.
+    pub fn entity_at(&self, col_idx: usize) -> Option<Entity> {
+        self.entities.entity_at(col_idx)
+    }
+
+    /// Turns the matrix into another one with `f`, e.g., to extend, reshape or append it, keeping the entities.
+    pub fn map<M2>(self, f: impl FnOnce(M) -> M2) -> World<M2> {
+        World {
+            mat: f(self.mat),
+            entities: self.entities,
+        }
+    }
+
+    /// Moves the rows of the matrix `M2` out of this world, as in `HMat::split`. Both halves keep the entities.
+    pub fn split<M2, Directive>(self) -> (World<M2>, World<<M2 as Split<M, Directive>>::Rem>)
+    where
+        M2: Split<M, Directive>,
+    {
+        let (mat, rem) = M2::split(self.mat);
+        (
+            World {
+                mat,
+                entities: self.entities.clone(),
+            },
+            World {
+                mat: rem,
+                entities: self.entities,
+            },
+        )
+    }
+}
+
+impl<M> Deref for World<M> {
+    type Target = M;
+    fn deref(&self) -> &M {
+        &self.mat
+    }
+}
+
+impl<T, R, S> World<HMat<T, R, S>> {
+    /// Spawns a new entity, reusing the column of a despawned entity if possible. The existing columns of the matrix are never reused unless they are removed.
+    pub fn spawn(&mut self) -> Entity
+    where
+        HMat<T, R, S>: NumCols,
+    {
+        self.entities.reserve(self.mat.num_cols());
+        self.entities.spawn()
+    }
+
+    /// Places the given column `col` at a free column index and returns it, reusing the index of a removed column if possible.
+    /// Unlike `HMat::push_col`, the free indexes come from the entity allocator, so the columns of the alive entities without elements are never reused.
+    pub fn push_col(&mut self, col: HCol<T, <HMat<T, R, S> as PlaceCol<T>>::Rem>) -> usize
+    where
+        HMat<T, R, S>: PlaceCol<T> + NumCols,
+    {
+        let col_idx = self.spawn().index;
+        self.mat.place_col(col_idx, col);
+        col_idx
+    }
+
+    /// Removes and returns the column at the given column index `col_idx`, making the index available for reuse.
+    pub fn remove_col(&mut self, col_idx: usize) -> HCol<T, <HMat<T, R, S> as TakeCol<T>>::Rem>
+    where
+        HMat<T, R, S>: TakeCol<T> + NumCols,
+    {
+        self.entities.reserve(self.mat.num_cols());
+        self.entities.despawn_at(col_idx);
+        self.mat.remove_col(col_idx)
+    }
+
+    /// Moves every column of `other` to the end of this world, after its columns and its spawned entities, as in `HMat::append_cols`, and returns the new column indexes.
+    /// The entities of `other` are despawned.
+    pub fn append_cols(&mut self, other: &mut Self) -> Range<usize>
+    where
+        HMat<T, R, S>: AppendCols + NumCols,
+    {
+        self.mat.pad_cols(self.entities.slots.len());
+        let cols = self.mat.append_cols(&mut other.mat);
+        self.entities.reserve(cols.end);
+        for col_idx in 0..other.entities.slots.len() {
+            other.entities.despawn_at(col_idx);
+        }
+        cols
+    }
+
+    /// Despawns the given entity, removing and returning its column. Returns `None` if the entity was not alive.
+    #[allow(clippy::type_complexity)]
+    pub fn despawn(&mut self, entity: Entity) -> Option<HCol<T, <HMat<T, R, S> as TakeCol<T>>::Rem>>
+    where
+        HMat<T, R, S>: TakeCol<T>,
+    {
+        if !self.entities.despawn(entity) {
+            return None;
+        }
+        Some(self.mat.take_col(entity.index))
+    }
+
+    /// Returns a reference to the column of the given entity, or `None` if the entity is not alive.
+    #[allow(clippy::type_complexity)]
+    pub fn get_entity_col_ref<'a>(
+        &'a self,
+        entity: Entity,
+    ) -> Option<HCol<&'a T, <HMat<T, R, S> as AccessColRef<'a, T>>::Rem>>
+    where
+        HMat<T, R, S>: AccessColRef<'a, T>,
+    {
+        self.is_alive(entity)
+            .then(|| self.mat.get_col_ref(entity.index))
+    }
+
+    /// Returns a mutable reference to the column of the given entity, or `None` if the entity is not alive.
+    #[allow(clippy::type_complexity)]
+    pub fn get_entity_col_mut<'a>(
+        &'a mut self,
+        entity: Entity,
+    ) -> Option<HCol<&'a mut T, <HMat<T, R, S> as AccessColMut<'a, T>>::Rem>>
+    where
+        HMat<T, R, S>: AccessColMut<'a, T>,
+    {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        Some(self.mat.get_col_mut(entity.index))
+    }
+
+    /// Removes and returns the column of the given entity, or `None` if the entity is not alive. The entity stays alive.
+    #[allow(clippy::type_complexity)]
+    pub fn take_entity_col(
+        &mut self,
+        entity: Entity,
+    ) -> Option<HCol<T, <HMat<T, R, S> as TakeCol<T>>::Rem>>
+    where
+        HMat<T, R, S>: TakeCol<T>,
+    {
+        self.is_alive(entity)
+            .then(|| self.mat.take_col(entity.index))
+    }
+
+    /// Places the given column `col` at the column of the given entity. Returns the column back if the entity is not alive.
+    #[allow(clippy::type_complexity)]
+    pub fn place_entity_col(
+        &mut self,
+        entity: Entity,
+        col: HCol<T, <HMat<T, R, S> as PlaceCol<T>>::Rem>,
+    ) -> Result<(), HCol<T, <HMat<T, R, S> as PlaceCol<T>>::Rem>>
+    where
+        HMat<T, R, S>: PlaceCol<T>,
+    {
+        if !self.is_alive(entity) {
+            return Err(col);
+        }
+        self.mat.place_col(entity.index, col);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn world() {
+        let mut world = World::new(
+            HMat::<usize, ()>::new_with([Some(0), Some(1)])
+                .extend_with::<f32, _>([Some(0.5)])
+                .extend_with::<i32, _>([None, Some(-1)]),
+        );
+        // The existing columns are never handed out.
+        let entity = world.spawn();
+        assert_eq!(entity.index(), 2);
+        // The entities survive the changes of the shape...
+        let world = world.map(|mat| mat.reshape::<HMat<f32, HMat<usize, HMat<i32, ()>>>, _>());
+        assert!(world.is_alive(entity));
+        let world = world.map(|mat| mat.extend::<char>());
+        let (part, rest): (World<HMat<i32, HMat<usize, ()>>>, _) = world.split();
+        let rest: World<HMat<char, HMat<f32, ()>>> = rest;
+        assert!(part.is_alive(entity) && rest.is_alive(entity));
+        // ... while the matrix is reached through `Deref`.
+        assert_eq!(rest.get_row_ref::<f32, _>(), &Row::from_iter([Some(0.5)]));
+        let world = rest.map(|mat| mat.append(part.into_inner()));
+        assert_eq!(world.get_col_ref(1).get::<&i32, _>(), Some(&&-1));
+        assert!(world.is_alive(entity));
+    }
+}
@@ -1,25 +1,131 @@
 use std::marker::PhantomData;
 
-use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{AccessRowMut, HMat};
 
+mod kind;
 mod merge;
 mod new_writer;
 mod row_mod;
 mod sub_writer;
+mod undo;
 
+pub use kind::*;
 pub use merge::*;
 pub use new_writer::*;
 pub use row_mod::*;
 pub use sub_writer::*;
+pub use undo::*;
 
 /// A writer that can store a list of modifications, i.e., `RowMod`s that can be applied to a `HMat` in the future. Can be useful when it is not possible to maintain a mutable reference to the original matrix.
-/// Note that the modifications are **NOT** applied in the same order they are appended to the writer. The order is always: `SetCol`, `UpdateCol`, and then `UnsetCol`.
-pub struct HMatWriter<T, R> {
-    pub(crate) row_mods: Vec<RowMod<T>>,
+/// Note that by default the modifications are **NOT** applied in the same order they are appended to the writer. The order is always: `SetCol`, `UpdateCol` or `Patch`, and then `UnsetCol`.
+/// A writer created with `new_writer_ordered`, or any writer turned with `with_order`, applies them in the order they were appended instead, see `WriteOrder`.
+/// The writer kind `K` determines the closures that can be queued with `update_col`. A `Sendable` writer, created with `new_send_writer`, is `Send` and `Sync` if its elements are.
+/// A `Commands` writer, created with `new_command_writer`, queues data-only patches with `patch_col` instead of closures, and is the only kind that can be serialized.
+/// The conflict policies of the rows, which `merge_checked` uses, are not serialized, and a deserialized writer has the default ones.
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// let mat = HMat::<i32, ()>::new();
+/// let mut writer = mat.new_writer();
+/// writer.update_col(0, |elem: &mut i32| *elem += 1);
+/// serde_json::to_vec(&writer).unwrap();
+/// ```
+pub struct HMatWriter<T, R, K: WriterKind<T> = Local> {
+    pub(crate) row_mods: Vec<RowMod<T, K>>,
+    pub(crate) order: WriteOrder,
+    pub(crate) policy: ConflictPolicy<T>,
     pub(crate) rem: R,
-    pub(crate) pd: PhantomData<*const T>,
+    pub(crate) pd: PhantomData<K>,
+}
+
+/// The serialized form of a `Commands` writer.
+#[derive(Serialize)]
+#[serde(rename = "HMatWriter")]
+struct HMatWriterRef<'a, M, R> {
+    row_mods: &'a [M],
+    order: WriteOrder,
+    rem: &'a R,
+}
+
+/// The deserialized form of a `Commands` writer.
+#[derive(Deserialize)]
+#[serde(rename = "HMatWriter")]
+struct HMatWriterData<M, R> {
+    row_mods: Vec<M>,
+    order: WriteOrder,
+    rem: R,
+}
+
+impl<T, R: Serialize, P: PatchSet<T>> Serialize for HMatWriter<T, R, Commands<P>>
+where
+    RowMod<T, Commands<P>>: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        HMatWriterRef {
+            row_mods: &self.row_mods,
+            order: self.order,
+            rem: &self.rem,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, R: Deserialize<'de>, P: PatchSet<T>> Deserialize<'de> for HMatWriter<T, R, Commands<P>>
+where
+    RowMod<T, Commands<P>>: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = HMatWriterData::deserialize(deserializer)?;
+        Ok(HMatWriter {
+            row_mods: data.row_mods,
+            order: data.order,
+            policy: Default::default(),
+            rem: data.rem,
+            pd: PhantomData,
+        })
+    }
+}
+
+/// The order in which the modifications of a writer are applied to each row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteOrder {
+    /// Applies the modifications by their kinds: `SetCol`, `UpdateCol` or `Patch`, `UnsetCol`, and then `Resize`. The modifications of the same kind keep their order.
+    #[default]
+    Priority,
+    /// Applies the modifications in the order they were appended, e.g., so that an `UnsetCol` followed by a `SetCol` replaces the element.
+    Insertion,
+}
+
+impl WriteOrder {
+    /// Arranges the given modifications in the order they should be applied.
+    pub(crate) fn arrange<T, K: WriterKind<T>>(
+        self,
+        mut row_mods: Vec<RowMod<T, K>>,
+    ) -> Vec<RowMod<T, K>> {
+        if self == WriteOrder::Priority {
+            row_mods.sort_by_key(|row_mod| row_mod.priority());
+        }
+        row_mods
+    }
+}
+
+/// Internal trait used to set the `WriteOrder` of every row of a writer.
+pub trait SetWriteOrder {
+    /// Sets the ordering policy of this row and the remaining rows.
+    fn set_order(&mut self, order: WriteOrder);
+}
+
+impl SetWriteOrder for () {
+    fn set_order(&mut self, _order: WriteOrder) {}
+}
+
+impl<T, R: SetWriteOrder, K: WriterKind<T>> SetWriteOrder for HMatWriter<T, R, K> {
+    fn set_order(&mut self, order: WriteOrder) {
+        self.order = order;
+        self.rem.set_order(order);
+    }
 }
 
 /// Internal type used for the recursive implementations of the `ApplyWriter` trait.
@@ -30,43 +136,54 @@ pub trait ApplyWriter<W, Directive> {
     fn apply(&mut self, w: W);
 }
 
-impl<D1, D2, R, A, Awt, Hh, Hr>
-    ApplyWriter<HMatWriter<D1, HMatWriter<D2, R>>, ApplyWriterDirective<A, Awt>> for HMat<Hh, Hr>
+impl<D1, D2, R, K: WriterKind<D1> + WriterKind<D2>, A, Awt, Hh, Hr, Hs>
+    ApplyWriter<HMatWriter<D1, HMatWriter<D2, R, K>, K>, ApplyWriterDirective<A, Awt>>
+    for HMat<Hh, Hr, Hs>
 where
     Self: AccessRowMut<D1, A>,
-    Self: ApplyWriter<HMatWriter<D2, R>, Awt>,
+    Self: ApplyWriter<HMatWriter<D2, R, K>, Awt>,
 {
-    fn apply(&mut self, w: HMatWriter<D1, HMatWriter<D2, R>>) {
+    fn apply(&mut self, w: HMatWriter<D1, HMatWriter<D2, R, K>, K>) {
         let row_mut = self.get_row_mut();
-        w.row_mods
-            .into_iter()
-            .sorted_by_key(|row_mod| row_mod.priority())
-            .for_each(|row_mod| {
-                row_mod.apply(row_mut);
-            });
+        w.order.arrange(w.row_mods).into_iter().for_each(|row_mod| {
+            row_mod.apply(row_mut);
+        });
         self.apply(w.rem);
     }
 }
 
-impl<D, A, Hh, Hr> ApplyWriter<HMatWriter<D, ()>, ApplyWriterDirective<A, ()>> for HMat<Hh, Hr>
+impl<D, K: WriterKind<D>, A, Hh, Hr, Hs>
+    ApplyWriter<HMatWriter<D, (), K>, ApplyWriterDirective<A, ()>> for HMat<Hh, Hr, Hs>
 where
     Self: AccessRowMut<D, A>,
 {
-    fn apply(&mut self, w: HMatWriter<D, ()>) {
+    fn apply(&mut self, w: HMatWriter<D, (), K>) {
         let row_mut = self.get_row_mut();
-        w.row_mods
-            .into_iter()
-            .sorted_by_key(|row_mod| row_mod.priority())
-            .for_each(|row_mod| {
-                row_mod.apply(row_mut);
-            });
+        w.order.arrange(w.row_mods).into_iter().for_each(|row_mod| {
+            row_mod.apply(row_mut);
+        });
     }
 }
 
-impl<T, R> HMatWriter<T, R> {
+impl<T, R, K: WriterKind<T>> HMatWriter<T, R, K> {
+    /// Returns this writer with the given ordering policy for all of its rows, e.g., to make a `Sendable` or `Commands` writer apply its modifications in the order they were queued.
+    pub fn with_order(mut self, order: WriteOrder) -> Self
+    where
+        Self: SetWriteOrder,
+    {
+        self.set_order(order);
+        self
+    }
+
+    /// Returns the ordering policy of this writer.
+    pub fn order(&self) -> WriteOrder {
+        self.order
+    }
+
     pub fn set_col<D, A>(&mut self, col_idx: usize, new_val: D)
     where
-        Self: GetSubWriter<D, A>,
+        Self: GetSubWriter<D, A, K>,
+        K: WriterKind<D>,
     {
         self.sub_writer_mut()
             .row_mods
@@ -75,35 +192,87 @@ impl<T, R> HMatWriter<T, R> {
 
     pub fn unset_col<D, A>(&mut self, col_idx: usize)
     where
-        Self: GetSubWriter<D, A>,
+        Self: GetSubWriter<D, A, K>,
+        K: WriterKind<D>,
     {
         self.sub_writer_mut()
             .row_mods
             .push(RowMod::UnsetCol(col_idx));
     }
 
-    pub fn update_col<D, A>(&mut self, col_idx: usize, f: impl FnOnce(&mut D) + 'static)
+    pub fn update_col<D, A, F>(&mut self, col_idx: usize, f: F)
     where
-        Self: GetSubWriter<D, A>,
+        Self: GetSubWriter<D, A, K>,
+        F: FnOnce(&mut D) + 'static,
+        K: BoxUpdate<D, F>,
     {
         self.sub_writer_mut()
             .row_mods
-            .push(RowMod::UpdateCol(col_idx, Box::new(f)));
+            .push(RowMod::UpdateCol(col_idx, K::box_update(f)));
     }
 
-    pub fn merge<T2, R2, Directive>(&mut self, other: HMatWriter<T2, R2>)
+    pub fn patch_col<D, A>(&mut self, col_idx: usize, patch: <K as WriterKind<D>>::Patch)
     where
-        Self: Merge<HMatWriter<T2, R2>, Directive>,
+        Self: GetSubWriter<D, A, K>,
+        K: WriterKind<D>,
     {
-        Merge::<HMatWriter<T2, R2>, Directive>::merge(self, other);
+        self.sub_writer_mut()
+            .row_mods
+            .push(RowMod::Patch(col_idx, patch));
+    }
+
+    pub fn merge<T2, R2, Directive>(&mut self, other: HMatWriter<T2, R2, K>)
+    where
+        Self: Merge<HMatWriter<T2, R2, K>, Directive>,
+        K: WriterKind<T2>,
+    {
+        Merge::<HMatWriter<T2, R2, K>, Directive>::merge(self, other);
+    }
+
+    /// Sets the policy that `merge_checked` uses for the conflicts on the row of type `D`. The default policy is `ConflictPolicy::Error`.
+    pub fn set_conflict_policy<D, A>(&mut self, policy: ConflictPolicy<D>)
+    where
+        Self: GetSubWriter<D, A, K>,
+        K: WriterKind<D>,
+    {
+        self.sub_writer_mut().policy = policy;
+    }
+
+    /// Merges this with `other` like `merge`, but first looks for the cells that both of them set or unset.
+    /// The conflicts are resolved by the policies of this writer, and returned. The losing side of a conflict only loses its sets and unsets on the cell, not its updates and patches.
+    /// If a conflict is on a row with the `Error` policy, nothing is merged and `other` is returned back.
+    pub fn merge_checked<T2, R2, Directive>(
+        &mut self,
+        other: HMatWriter<T2, R2, K>,
+    ) -> Result<Vec<Conflict>, MergeError<HMatWriter<T2, R2, K>>>
+    where
+        Self: MergeChecked<HMatWriter<T2, R2, K>, Directive>,
+        K: WriterKind<T2>,
+    {
+        let mut conflicts = Vec::new();
+        match MergeChecked::<HMatWriter<T2, R2, K>, Directive>::check_conflicts(
+            self,
+            &other,
+            &mut conflicts,
+        ) {
+            Some(losses) => {
+                MergeChecked::<HMatWriter<T2, R2, K>, Directive>::merge_resolved(
+                    self, other, losses,
+                );
+                Ok(conflicts)
+            }
+            None => Err(MergeError { conflicts, other }),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::*;
+    use std::{any::TypeId, collections::HashSet};
 
-    use super::new_writer::NewWriter;
+    use serde::{Deserialize, Serialize};
+
+    use crate::*;
 
     #[test]
     fn basic() {
@@ -137,6 +306,217 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ordered() {
+        let mut mat = HMat::<usize, ()>::new().extend::<i32>();
+        mat.get_row_mut::<i32, _>().place(0, 1);
+        mat.get_row_mut::<i32, _>().place(1, 1);
+        // By default, the unsets are applied last.
+        let mut writer = mat.new_writer();
+        writer.unset_col::<i32, _>(0);
+        writer.set_col(0, 2);
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<i32, _>().get(0), None);
+        // An ordered writer replaces the element instead...
+        let mut writer = mat.new_writer_ordered();
+        writer.unset_col::<i32, _>(1);
+        writer.set_col(1, 2);
+        writer.update_col(1, |val: &mut i32| *val *= 10);
+        // ... and an update queued before a set does not see the new element.
+        writer.update_col(0, |val: &mut i32| *val = -1);
+        writer.set_col(0, 3);
+        writer.set_col(2, 4);
+        writer.unset_col::<i32, _>(2);
+        writer.set_col::<usize, _>(0, 5);
+        mat.apply(writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(3), Some(20), None])
+        );
+        assert_eq!(mat.get_row_ref::<usize, _>().get(0), Some(&5));
+        // Every kind of writer can be ordered.
+        let mut writer = mat.new_send_writer().with_order(WriteOrder::Insertion);
+        assert_eq!(writer.rem.order, WriteOrder::Insertion);
+        writer.unset_col::<usize, _>(0);
+        writer.set_col::<usize, _>(0, 6);
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<usize, _>().get(0), Some(&6));
+        let mut writer = mat
+            .new_command_writer::<Unsupported>()
+            .with_order(WriteOrder::Insertion);
+        assert_eq!(writer.order(), WriteOrder::Insertion);
+        writer.unset_col::<i32, _>(0);
+        writer.set_col(0, 7);
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<i32, _>().get(0), Some(&7));
+    }
+
+    #[test]
+    fn send() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        let mut mat = HMat::<usize, ()>::new().extend::<i32>();
+        mat.get_row_mut::<i32, _>().place(0, 1);
+        let mut writer: hmat_writer_type![Sendable; i32, usize] = mat.new_send_writer();
+        assert_send_sync(&writer);
+        // Record the modifications on worker threads, and merge them on this thread.
+        let handles: Vec<_> = (0..2)
+            .map(|col_idx| {
+                let mut worker_writer = mat.new_send_writer();
+                std::thread::spawn(move || {
+                    worker_writer.set_col::<usize, _>(col_idx, col_idx * 10);
+                    worker_writer.update_col(0, |val: &mut i32| *val += 1);
+                    worker_writer
+                })
+            })
+            .collect();
+        for handle in handles {
+            writer.merge(handle.join().unwrap());
+        }
+        // The merged writer can be shared with other threads as well.
+        std::thread::scope(|scope| {
+            scope.spawn(|| assert_eq!(writer.rem.row_mods.len(), 2));
+        });
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<i32, _>(), &Row::from_iter([Some(3)]));
+        assert_eq!(
+            mat.get_row_ref::<usize, _>(),
+            &Row::from_iter([Some(0), Some(10)])
+        );
+    }
+
+    #[test]
+    fn commands() {
+        #[derive(Serialize, Deserialize)]
+        struct Add(i32);
+        impl Patch<i32> for Add {
+            fn apply(self, elem: &mut i32) {
+                *elem += self.0;
+            }
+        }
+        #[derive(Serialize, Deserialize)]
+        struct Scale(f32);
+        impl Patch<f32> for Scale {
+            fn apply(self, elem: &mut f32) {
+                *elem *= self.0;
+            }
+        }
+        // Each row has its own patch type, and the usize row has none.
+        struct GamePatches;
+        impl PatchSet<i32> for GamePatches {
+            type Patch = Add;
+        }
+        impl PatchSet<f32> for GamePatches {
+            type Patch = Scale;
+        }
+        impl PatchSet<usize> for GamePatches {
+            type Patch = Unsupported;
+        }
+        let mut mat = HMat::<usize, ()>::new_with([Some(7), Some(8)])
+            .extend_with::<f32, _>([Some(1.5), Some(2.5)])
+            .extend::<i32>();
+        let mut writer = mat.new_command_writer::<GamePatches>();
+        writer.set_col(1, 4);
+        writer.patch_col::<i32, _>(1, Add(2));
+        writer.patch_col::<f32, _>(0, Scale(2.0));
+        writer.unset_col::<i32, _>(0);
+        // The writer is shipped as bytes and applied on the other side.
+        let bytes = serde_json::to_vec(&writer).unwrap();
+        let writer: hmat_writer_type![Commands<GamePatches>; i32, f32, usize] =
+            serde_json::from_slice(&bytes).unwrap();
+        mat.apply(writer);
+        // Only the targeted cells are patched.
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([None, Some(6)])
+        );
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(3.0), Some(2.5)])
+        );
+        assert_eq!(
+            mat.get_row_ref::<usize, _>(),
+            &Row::from_iter([Some(7), Some(8)])
+        );
+    }
+
+    #[test]
+    fn merge_checked() {
+        let mut mat = HMat::<usize, ()>::new().extend::<i32>();
+        let new_writer = |mat: &HMat<i32, HMat<usize, ()>>, val: i32| {
+            let mut writer = mat.new_writer();
+            writer.set_col(0, val);
+            writer.update_col(0, move |elem: &mut i32| *elem += val);
+            writer.set_col(1, val);
+            writer.unset_col::<usize, _>(val as usize);
+            writer
+        };
+        // By default, the conflicts reject the merge.
+        let mut writer = new_writer(&mat, 1);
+        let err = writer.merge_checked(new_writer(&mat, 2)).unwrap_err();
+        assert!(err.conflicts().iter().all(Conflict::is_row::<i32>));
+        assert_eq!(
+            err.conflicts()
+                .iter()
+                .map(Conflict::col_idx)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        // The writers are merged when the conflicts are resolved, also by a capturing closure.
+        let first_cols = HashSet::from([0]);
+        writer.set_conflict_policy::<i32, _>(ConflictPolicy::resolve(move |col_idx, _, _| {
+            if first_cols.contains(&col_idx) {
+                ConflictSide::First
+            } else {
+                ConflictSide::Last
+            }
+        }));
+        let conflicts = writer.merge_checked(err.into_other()).ok();
+        assert_eq!(conflicts.map(|conflicts| conflicts.len()), Some(2));
+        mat.apply(writer);
+        // The losing side only loses its set, and its update still applies.
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(4), Some(2)])
+        );
+        // Only the cells that are set or unset on both sides conflict.
+        let mut writer = new_writer(&mat, 3);
+        writer.set_conflict_policy::<i32, _>(ConflictPolicy::FirstWins);
+        let mut other = mat.new_writer();
+        other.unset_col::<i32, _>(1);
+        other.update_col(0, |elem: &mut i32| *elem *= 10);
+        other.set_col::<usize, _>(0, 7);
+        let conflicts = writer.merge_checked(other).ok();
+        assert_eq!(conflicts.map(|conflicts| conflicts.len()), Some(1));
+        mat.apply(writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(60), Some(3)])
+        );
+        assert_eq!(mat.get_row_ref::<usize, _>(), &Row::from_iter([Some(7)]));
+        // The values that each side writes follow the order it is applied in after the merge.
+        let mut writer = mat.new_writer_ordered();
+        writer.set_col(0, 8);
+        writer.set_conflict_policy::<i32, _>(ConflictPolicy::resolve(|_, _, last| {
+            assert_eq!(last, None);
+            ConflictSide::Last
+        }));
+        let mut other = mat.new_writer();
+        other.unset_col::<i32, _>(0);
+        other.set_col(0, 9);
+        assert!(writer.merge_checked(other).is_ok());
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<i32, _>().get(0), None);
+        // The conflicts name the rows by their types, and by their positions in the receiving writer.
+        let mut writer = mat.new_writer();
+        writer.unset_col::<usize, _>(0);
+        let mut other = mat.new_writer();
+        other.set_col::<usize, _>(0, 1);
+        let err = writer.merge_checked(other).unwrap_err();
+        assert!(err.conflicts()[0].is_row::<usize>());
+        assert_eq!(err.conflicts()[0].row_type(), TypeId::of::<usize>());
+        assert_eq!(err.conflicts()[0].row_idx(), 1);
+    }
+
     #[test]
     fn merge() {
         let mut mat = HMat::<usize, ()>::new().extend::<f32>().extend::<i32>();
@@ -165,5 +545,26 @@ mod tests {
             let ref_mat: HMatRef<i32, ()> = HMatRef::slice(&mat);
             assert_eq!(ref_mat.get_row_ref(), &Row::<i32>::from_iter([Some(5)]));
         }
+        // The receiving writer keeps its order, and the merged modifications are arranged by theirs.
+        let mut writer = mat.new_writer_ordered();
+        writer.unset_col::<i32, _>(0);
+        writer.set_col(0, 1);
+        let mut other = mat.new_writer();
+        other.unset_col::<i32, _>(1);
+        other.set_col(1, 2);
+        writer.merge(other);
+        assert_eq!(writer.order(), WriteOrder::Insertion);
+        mat.apply(writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(1), None])
+        );
+        let mut writer = mat.new_writer();
+        writer.merge(mat.new_writer_ordered());
+        assert_eq!(writer.order(), WriteOrder::Priority);
+        writer.unset_col::<i32, _>(0);
+        writer.set_col(0, 42);
+        mat.apply(writer);
+        assert_eq!(mat.get_row_ref::<i32, _>().get(0), None);
     }
 }
@@ -0,0 +1,104 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Patch;
+
+/// Represents the kind of a writer, which determines the closures that its `UpdateCol` modifications and the data that its `Patch` modifications on the elements of type `T` can hold.
+pub trait WriterKind<T> {
+    /// The boxed closure type that updates an element of type `T`.
+    type Update: ?Sized;
+    /// The data-only modification type of the elements of type `T`, see `Patch`.
+    type Patch: Patch<T>;
+    /// Calls the given update closure on the element `elem`.
+    fn call_update(f: Box<Self::Update>, elem: &mut T);
+}
+
+/// Represents a writer kind that can box the closure `F` as an update of the elements of type `T`.
+pub trait BoxUpdate<T, F>: WriterKind<T> {
+    /// Boxes the given closure.
+    fn box_update(f: F) -> Box<Self::Update>;
+}
+
+/// Maps every row type `T` of a `Commands` writer to the type of the patches that target it.
+/// The rows without patches map to `Unsupported`. A patch of another row type cannot be queued:
+///
+/// ```compile_fail
+/// use h_mat::*;
+/// struct Add(i32);
+/// impl Patch<i32> for Add {
+///     fn apply(self, elem: &mut i32) {
+///         *elem += self.0;
+///     }
+/// }
+/// struct Patches;
+/// impl PatchSet<i32> for Patches {
+///     type Patch = Add;
+/// }
+/// impl PatchSet<f32> for Patches {
+///     type Patch = Unsupported;
+/// }
+/// let mat = HMat::<f32, ()>::new().extend::<i32>();
+/// let mut writer = mat.new_command_writer::<Patches>();
+/// writer.patch_col::<f32, _>(0, Add(1));
+/// ```
+pub trait PatchSet<T> {
+    /// The type of the patches that target the rows of type `T`.
+    type Patch: Patch<T>;
+}
+
+impl<T> PatchSet<T> for Unsupported {
+    type Patch = Unsupported;
+}
+
+/// An uninhabited type that stands for the modifications that a writer kind does not support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unsupported {}
+
+/// The default writer kind, whose update closures can capture anything.
+pub struct Local;
+
+/// The writer kind whose update closures must be `Send` and `Sync`, so that the writer can be sent to and shared with other threads, e.g., recorded on a worker thread and applied on the main thread.
+pub struct Sendable;
+
+/// The writer kind that holds no closures, but the data-only modifications that `P` maps each row type to, so that the writer can be serialized, e.g., to be logged or replayed.
+pub struct Commands<P>(PhantomData<P>);
+
+impl<T> WriterKind<T> for Local {
+    type Update = dyn FnOnce(&mut T);
+    type Patch = Unsupported;
+
+    fn call_update(f: Box<Self::Update>, elem: &mut T) {
+        f(elem)
+    }
+}
+
+impl<T, F: FnOnce(&mut T) + 'static> BoxUpdate<T, F> for Local {
+    fn box_update(f: F) -> Box<Self::Update> {
+        Box::new(f)
+    }
+}
+
+impl<T> WriterKind<T> for Sendable {
+    type Update = dyn FnOnce(&mut T) + Send + Sync;
+    type Patch = Unsupported;
+
+    fn call_update(f: Box<Self::Update>, elem: &mut T) {
+        f(elem)
+    }
+}
+
+impl<T, F: FnOnce(&mut T) + Send + Sync + 'static> BoxUpdate<T, F> for Sendable {
+    fn box_update(f: F) -> Box<Self::Update> {
+        Box::new(f)
+    }
+}
+
+impl<T, P: PatchSet<T>> WriterKind<T> for Commands<P> {
+    type Update = Unsupported;
+    type Patch = P::Patch;
+
+    fn call_update(f: Box<Self::Update>, _: &mut T) {
+        match *f {}
+    }
+}
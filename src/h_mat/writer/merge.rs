@@ -1,6 +1,6 @@
-use std::marker::PhantomData;
+use std::{any::TypeId, collections::HashSet, fmt, marker::PhantomData};
 
-use crate::{h_mat::writer::sub_writer::GetSubWriter, HMatWriter};
+use crate::{h_mat::writer::sub_writer::GetSubWriter, HMatWriter, RowMod, WriteOrder, WriterKind};
 
 /// Represents a recursive type that can be merged with another recursive type.
 pub trait Merge<Other, Directive> {
@@ -11,26 +11,299 @@ pub trait Merge<Other, Directive> {
 /// Internal type used for the recursive implementations of the `Merge` trait.
 pub struct MergeDirective<Head, Tail>(PhantomData<Head>, PhantomData<Tail>);
 
-impl<T, D, R, A1, A2, DirectiveTail>
-    Merge<HMatWriter<D, R>, MergeDirective<A1, MergeDirective<A2, DirectiveTail>>> for T
+impl<T, D, R, K: WriterKind<D>, A1, A2, DirectiveTail>
+    Merge<HMatWriter<D, R, K>, MergeDirective<A1, MergeDirective<A2, DirectiveTail>>> for T
 where
-    T: GetSubWriter<D, A1> + Merge<R, MergeDirective<A2, DirectiveTail>>,
+    T: GetSubWriter<D, A1, K> + Merge<R, MergeDirective<A2, DirectiveTail>>,
 {
-    fn merge(&mut self, other: HMatWriter<D, R>) {
-        self.sub_writer_mut()
-            .row_mods
-            .extend(other.row_mods.into_iter());
+    fn merge(&mut self, other: HMatWriter<D, R, K>) {
+        append_row_mods(self.sub_writer_mut(), other.row_mods, other.order);
         self.merge(other.rem);
     }
 }
 
-impl<T, D, A> Merge<HMatWriter<D, ()>, MergeDirective<A, ()>> for T
+impl<T, D, K: WriterKind<D>, A> Merge<HMatWriter<D, (), K>, MergeDirective<A, ()>> for T
 where
-    T: GetSubWriter<D, A>,
+    T: GetSubWriter<D, A, K>,
 {
-    fn merge(&mut self, other: HMatWriter<D, ()>) {
-        self.sub_writer_mut()
-            .row_mods
-            .extend(other.row_mods.into_iter())
+    fn merge(&mut self, other: HMatWriter<D, (), K>) {
+        append_row_mods(self.sub_writer_mut(), other.row_mods, other.order)
+    }
+}
+
+/// Appends the modifications `row_mods`, which are applied in the order `order`, to the subwriter `first`, which keeps its own order.
+/// The modifications are arranged by `order` first, so they keep their meaning when `first` applies them in the insertion order.
+fn append_row_mods<D, R, K: WriterKind<D>>(
+    first: &mut HMatWriter<D, R, K>,
+    row_mods: Vec<RowMod<D, K>>,
+    order: WriteOrder,
+) {
+    first.row_mods.extend(order.arrange(row_mods));
+}
+
+/// Returns the order in which the modifications of a writer ordered by `order` are applied once they are appended to a writer ordered by `receiver`.
+fn appended_order(receiver: WriteOrder, order: WriteOrder) -> WriteOrder {
+    match receiver {
+        WriteOrder::Priority => WriteOrder::Priority,
+        WriteOrder::Insertion => order,
+    }
+}
+
+/// A cell that is written, i.e., set or unset, by both of the writers that are merged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub(crate) row_type: TypeId,
+    pub(crate) row_idx: usize,
+    pub(crate) col_idx: usize,
+}
+
+impl Conflict {
+    /// Returns the `TypeId` of the elements of the row.
+    pub fn row_type(&self) -> TypeId {
+        self.row_type
+    }
+
+    /// Returns whether the conflict is on the row of type `T`.
+    pub fn is_row<T: 'static>(&self) -> bool {
+        self.row_type == TypeId::of::<T>()
+    }
+
+    /// Returns the position of the row in the receiving writer, starting from `0` at the head, which tells apart the rows of the same type.
+    pub fn row_idx(&self) -> usize {
+        self.row_idx
+    }
+
+    /// Returns the column index.
+    pub fn col_idx(&self) -> usize {
+        self.col_idx
+    }
+}
+
+/// The side of a merge whose modifications on a conflicting cell are kept, i.e., the receiving writer (`First`) or the merged writer (`Last`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictSide {
+    First,
+    Last,
+}
+
+/// Determines how `merge_checked` treats the conflicts on a row of type `T`. Only the sets and unsets of the kept side on a conflicting cell are merged, while the updates and patches of both sides are kept.
+/// The policies are not serialized with a `Commands` writer, so a deserialized writer has the default `Error` policy on every row.
+#[derive(Default)]
+pub enum ConflictPolicy<T> {
+    /// Rejects the merge altogether.
+    #[default]
+    Error,
+    /// Keeps the modifications of the receiving writer.
+    FirstWins,
+    /// Keeps the modifications of the merged writer.
+    LastWins,
+    /// Picks the side with the given closure, which receives the column index and the values that each side eventually writes (`None` for an unset).
+    /// The closure must be `Send` and `Sync`, so that it does not restrict the writer it is set on.
+    #[allow(clippy::type_complexity)]
+    Resolve(Box<dyn Fn(usize, Option<&T>, Option<&T>) -> ConflictSide + Send + Sync>),
+}
+
+impl<T> ConflictPolicy<T> {
+    /// Creates a `Resolve` policy from the given closure.
+    pub fn resolve<F>(f: F) -> Self
+    where
+        F: Fn(usize, Option<&T>, Option<&T>) -> ConflictSide + Send + Sync + 'static,
+    {
+        ConflictPolicy::Resolve(Box::new(f))
+    }
+}
+
+impl<T> fmt::Debug for ConflictPolicy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictPolicy::Error => write!(f, "Error"),
+            ConflictPolicy::FirstWins => write!(f, "FirstWins"),
+            ConflictPolicy::LastWins => write!(f, "LastWins"),
+            ConflictPolicy::Resolve(_) => write!(f, "Resolve(..)"),
+        }
+    }
+}
+
+/// Returned by `merge_checked` when a conflict could not be resolved. Holds every conflict and the writer that was not merged.
+#[derive(Debug)]
+pub struct MergeError<W> {
+    pub(crate) conflicts: Vec<Conflict>,
+    pub(crate) other: W,
+}
+
+impl<W> MergeError<W> {
+    /// Returns every conflict, including the resolvable ones.
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+
+    /// Returns the writer that was not merged.
+    pub fn into_other(self) -> W {
+        self.other
+    }
+}
+
+/// The conflicting cells of a row whose sets and unsets are dropped by `MergeChecked::merge_resolved`, on each side.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Losses {
+    pub(crate) first: HashSet<usize>,
+    pub(crate) last: HashSet<usize>,
+}
+
+/// Represents a recursive type that can be merged with another recursive type while checking for the conflicting writes.
+pub trait MergeChecked<Other, Directive> {
+    /// The `Losses` of every row, as a recursive list.
+    type Losses;
+    /// Collects the conflicts with `other` recursively, and resolves them by the policies of this writer. Returns `None` if any of them cannot be resolved.
+    fn check_conflicts(
+        &mut self,
+        other: &Other,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Option<Self::Losses>;
+    /// Merges this with `other` recursively, dropping the losing writes returned by `check_conflicts`.
+    fn merge_resolved(&mut self, other: Other, losses: Self::Losses);
+}
+
+/// Returns the column indexes that are written by both of the given lists, in increasing order.
+fn conflicting_cols<T, K: WriterKind<T>>(
+    first: &[RowMod<T, K>],
+    last: &[RowMod<T, K>],
+) -> Vec<usize> {
+    let first_writes: HashSet<_> = first.iter().filter_map(RowMod::written_col).collect();
+    let last_writes: HashSet<_> = last.iter().filter_map(RowMod::written_col).collect();
+    let mut cols: Vec<_> = first_writes.intersection(&last_writes).copied().collect();
+    cols.sort_unstable();
+    cols
+}
+
+/// Returns the value that the given modifications eventually write on the given column when applied in the given order.
+fn final_write<T, K: WriterKind<T>>(
+    row_mods: &[RowMod<T, K>],
+    order: WriteOrder,
+    col_idx: usize,
+) -> Option<&T> {
+    row_mods
+        .iter()
+        .enumerate()
+        .filter(|(_, row_mod)| row_mod.written_col() == Some(col_idx))
+        .max_by_key(|(pos, row_mod)| match order {
+            WriteOrder::Priority => (row_mod.priority(), *pos),
+            WriteOrder::Insertion => (0, *pos),
+        })
+        .and_then(|(_, row_mod)| match row_mod {
+            RowMod::SetCol(_, val) => Some(val),
+            _ => None,
+        })
+}
+
+/// Collects the conflicts between the subwriter `first` and `other`, and resolves them by the policy of `first`. Returns `None` if the policy is `Error` and there are conflicts.
+fn check_row<D: 'static, R1, R2, K: WriterKind<D>>(
+    first: &HMatWriter<D, R1, K>,
+    other: &HMatWriter<D, R2, K>,
+    row_idx: usize,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<Losses> {
+    let cols = conflicting_cols(&first.row_mods, &other.row_mods);
+    conflicts.extend(cols.iter().map(|&col_idx| Conflict {
+        row_type: TypeId::of::<D>(),
+        row_idx,
+        col_idx,
+    }));
+    let mut losses = Losses::default();
+    for col_idx in cols {
+        let side = match &first.policy {
+            ConflictPolicy::Error => return None,
+            ConflictPolicy::FirstWins => ConflictSide::First,
+            ConflictPolicy::LastWins => ConflictSide::Last,
+            ConflictPolicy::Resolve(resolve) => resolve(
+                col_idx,
+                final_write(&first.row_mods, first.order, col_idx),
+                final_write(
+                    &other.row_mods,
+                    appended_order(first.order, other.order),
+                    col_idx,
+                ),
+            ),
+        };
+        match side {
+            ConflictSide::First => losses.last.insert(col_idx),
+            ConflictSide::Last => losses.first.insert(col_idx),
+        };
+    }
+    Some(losses)
+}
+
+/// Merges the modifications of `other` into the subwriter `first`, dropping the sets and unsets of the losing side on the conflicting cells.
+fn merge_row<D, R1, R2, K: WriterKind<D>>(
+    first: &mut HMatWriter<D, R1, K>,
+    other: &mut HMatWriter<D, R2, K>,
+    losses: Losses,
+) {
+    first.row_mods.retain(
+        |row_mod| !matches!(row_mod.written_col(), Some(col_idx) if losses.first.contains(&col_idx)),
+    );
+    let row_mods = std::mem::take(&mut other.row_mods)
+        .into_iter()
+        .filter(
+            |row_mod| !matches!(row_mod.written_col(), Some(col_idx) if losses.last.contains(&col_idx)),
+        )
+        .collect();
+    append_row_mods(first, row_mods, other.order);
+}
+
+impl<T, D: 'static, R, K: WriterKind<D>, A1, A2, DirectiveTail>
+    MergeChecked<HMatWriter<D, R, K>, MergeDirective<A1, MergeDirective<A2, DirectiveTail>>> for T
+where
+    T: GetSubWriter<D, A1, K> + MergeChecked<R, MergeDirective<A2, DirectiveTail>>,
+{
+    type Losses = (
+        Losses,
+        <T as MergeChecked<R, MergeDirective<A2, DirectiveTail>>>::Losses,
+    );
+
+    fn check_conflicts(
+        &mut self,
+        other: &HMatWriter<D, R, K>,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Option<Self::Losses> {
+        // Every row is checked, so that all of the conflicts are collected.
+        let losses = check_row(
+            self.sub_writer_mut(),
+            other,
+            <T as GetSubWriter<D, A1, K>>::ROW_IDX,
+            conflicts,
+        );
+        let rem_losses = self.check_conflicts(&other.rem, conflicts);
+        Some((losses?, rem_losses?))
+    }
+
+    fn merge_resolved(&mut self, mut other: HMatWriter<D, R, K>, losses: Self::Losses) {
+        merge_row(self.sub_writer_mut(), &mut other, losses.0);
+        self.merge_resolved(other.rem, losses.1);
+    }
+}
+
+impl<T, D: 'static, K: WriterKind<D>, A> MergeChecked<HMatWriter<D, (), K>, MergeDirective<A, ()>>
+    for T
+where
+    T: GetSubWriter<D, A, K>,
+{
+    type Losses = Losses;
+
+    fn check_conflicts(
+        &mut self,
+        other: &HMatWriter<D, (), K>,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Option<Self::Losses> {
+        check_row(
+            self.sub_writer_mut(),
+            other,
+            <T as GetSubWriter<D, A, K>>::ROW_IDX,
+            conflicts,
+        )
+    }
+
+    fn merge_resolved(&mut self, mut other: HMatWriter<D, (), K>, losses: Self::Losses) {
+        merge_row(self.sub_writer_mut(), &mut other, losses);
     }
 }
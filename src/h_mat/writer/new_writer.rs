@@ -1,62 +1,72 @@
 use std::marker::PhantomData;
 
-use crate::{HMat, HMatRef, HMatWriter};
+use crate::{HMat, HMatRef, HMatWriter, WriteOrder, WriterKind};
 
-/// Represents a type that can return a writer corresponding to its fields.
-pub trait NewWriter<T> {
+/// Represents a type that can return a writer of the kind `K` corresponding to its fields.
+pub trait NewWriter<T, K: WriterKind<T>> {
     type Rem;
-    /// Returns a new writer that can be used to gather modifications and apply them at once.
-    fn new_writer(&self) -> HMatWriter<T, Self::Rem>;
+    /// Returns a new writer with the given ordering policy that can be used to gather modifications and apply them at once.
+    fn new_writer_with_order(&self, order: WriteOrder) -> HMatWriter<T, Self::Rem, K>;
 }
 
-impl<'a, T1, T2, R> NewWriter<T1> for HMatRef<'a, T1, HMatRef<'a, T2, R>>
+impl<'a, T1, T2, R, S1, S2, K> NewWriter<T1, K> for HMatRef<'a, T1, HMatRef<'a, T2, R, S2>, S1>
 where
-    HMatRef<'a, T2, R>: NewWriter<T2>,
+    K: WriterKind<T1> + WriterKind<T2>,
+    HMatRef<'a, T2, R, S2>: NewWriter<T2, K>,
 {
-    type Rem = HMatWriter<T2, <HMatRef<'a, T2, R> as NewWriter<T2>>::Rem>;
+    type Rem = HMatWriter<T2, <HMatRef<'a, T2, R, S2> as NewWriter<T2, K>>::Rem, K>;
 
-    fn new_writer(&self) -> HMatWriter<T1, Self::Rem> {
+    fn new_writer_with_order(&self, order: WriteOrder) -> HMatWriter<T1, Self::Rem, K> {
         HMatWriter {
             row_mods: Default::default(),
+            order,
+            policy: Default::default(),
             pd: PhantomData,
-            rem: self.rem.new_writer(),
+            rem: self.rem.new_writer_with_order(order),
         }
     }
 }
 
-impl<'a, T1> NewWriter<T1> for HMatRef<'a, T1, ()> {
+impl<'a, T1, S, K: WriterKind<T1>> NewWriter<T1, K> for HMatRef<'a, T1, (), S> {
     type Rem = ();
 
-    fn new_writer(&self) -> HMatWriter<T1, Self::Rem> {
+    fn new_writer_with_order(&self, order: WriteOrder) -> HMatWriter<T1, Self::Rem, K> {
         HMatWriter {
             row_mods: Default::default(),
+            order,
+            policy: Default::default(),
             pd: PhantomData,
             rem: (),
         }
     }
 }
 
-impl<T1, T2, R> NewWriter<T1> for HMat<T1, HMat<T2, R>>
+impl<T1, T2, R, S1, S2, K> NewWriter<T1, K> for HMat<T1, HMat<T2, R, S2>, S1>
 where
-    HMat<T2, R>: NewWriter<T2>,
+    K: WriterKind<T1> + WriterKind<T2>,
+    HMat<T2, R, S2>: NewWriter<T2, K>,
 {
-    type Rem = HMatWriter<T2, <HMat<T2, R> as NewWriter<T2>>::Rem>;
+    type Rem = HMatWriter<T2, <HMat<T2, R, S2> as NewWriter<T2, K>>::Rem, K>;
 
-    fn new_writer(&self) -> HMatWriter<T1, Self::Rem> {
+    fn new_writer_with_order(&self, order: WriteOrder) -> HMatWriter<T1, Self::Rem, K> {
         HMatWriter {
             row_mods: Default::default(),
+            order,
+            policy: Default::default(),
             pd: PhantomData,
-            rem: self.rem.new_writer(),
+            rem: self.rem.new_writer_with_order(order),
         }
     }
 }
 
-impl<T1> NewWriter<T1> for HMat<T1, ()> {
+impl<T1, S, K: WriterKind<T1>> NewWriter<T1, K> for HMat<T1, (), S> {
     type Rem = ();
 
-    fn new_writer(&self) -> HMatWriter<T1, Self::Rem> {
+    fn new_writer_with_order(&self, order: WriteOrder) -> HMatWriter<T1, Self::Rem, K> {
         HMatWriter {
             row_mods: Default::default(),
+            order,
+            policy: Default::default(),
             pd: PhantomData,
             rem: (),
         }
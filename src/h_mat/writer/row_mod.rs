@@ -1,22 +1,99 @@
-use crate::Row;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/// Represents a modification on a `Row<T>`.
-pub enum RowMod<T> {
+use crate::{Commands, Local, PatchSet, Row, RowStorage, Unsupported, WriterKind};
+
+/// Represents a data-only modification of an element of type `T`, e.g., a serializable enum of the supported changes.
+pub trait Patch<T> {
+    /// Applies this modification on the given element.
+    fn apply(self, elem: &mut T);
+}
+
+impl<T> Patch<T> for Unsupported {
+    fn apply(self, _: &mut T) {
+        match self {}
+    }
+}
+
+/// Represents a modification on a `Row<T>`. The writer kind `K` determines the type of the update closures and the patches.
+/// Only the modifications of `Commands` writers, which hold no closures, can be serialized.
+pub enum RowMod<T, K: WriterKind<T> = Local> {
     SetCol(usize, T),
     UnsetCol(usize),
-    UpdateCol(usize, Box<dyn FnOnce(&mut T)>),
+    UpdateCol(usize, Box<K::Update>),
+    Patch(usize, K::Patch),
+    /// Sets the length of the row, e.g., to shrink it back in the inverse writers returned by `apply_with_undo`.
+    Resize(usize),
+}
+
+/// The serialized form of a `RowMod` of a `Commands` writer, which has no `UpdateCol` modifications.
+#[derive(Serialize)]
+#[serde(rename = "RowMod")]
+enum RowModRef<'a, T, P> {
+    SetCol(usize, &'a T),
+    UnsetCol(usize),
+    Patch(usize, &'a P),
+    Resize(usize),
 }
 
-impl<T> RowMod<T> {
+/// The deserialized form of a `RowMod` of a `Commands` writer.
+#[derive(Deserialize)]
+#[serde(rename = "RowMod")]
+enum RowModData<T, P> {
+    SetCol(usize, T),
+    UnsetCol(usize),
+    Patch(usize, P),
+    Resize(usize),
+}
+
+impl<T: Serialize, P: PatchSet<T>> Serialize for RowMod<T, Commands<P>>
+where
+    P::Patch: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            RowMod::SetCol(col_idx, new_val) => RowModRef::SetCol(*col_idx, new_val),
+            RowMod::UnsetCol(col_idx) => RowModRef::UnsetCol(*col_idx),
+            RowMod::UpdateCol(_, f) => match **f {},
+            RowMod::Patch(col_idx, patch) => RowModRef::Patch(*col_idx, patch),
+            RowMod::Resize(len) => RowModRef::Resize(*len),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, P: PatchSet<T>> Deserialize<'de> for RowMod<T, Commands<P>>
+where
+    P::Patch: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match RowModData::deserialize(deserializer)? {
+            RowModData::SetCol(col_idx, new_val) => RowMod::SetCol(col_idx, new_val),
+            RowModData::UnsetCol(col_idx) => RowMod::UnsetCol(col_idx),
+            RowModData::Patch(col_idx, patch) => RowMod::Patch(col_idx, patch),
+            RowModData::Resize(len) => RowMod::Resize(len),
+        })
+    }
+}
+
+impl<T, K: WriterKind<T>> RowMod<T, K> {
     pub(crate) fn priority(&self) -> usize {
         match self {
             RowMod::SetCol(_, _) => 0,
-            RowMod::UpdateCol(_, _) => 10,
+            RowMod::UpdateCol(_, _) | RowMod::Patch(_, _) => 10,
             RowMod::UnsetCol(_) => 20,
+            RowMod::Resize(_) => 30,
+        }
+    }
+
+    /// Returns the index of the column this modification writes, i.e., sets or unsets.
+    pub(crate) fn written_col(&self) -> Option<usize> {
+        match self {
+            RowMod::SetCol(col_idx, _) | RowMod::UnsetCol(col_idx) => Some(*col_idx),
+            _ => None,
         }
     }
 
-    pub(crate) fn apply(self, row: &mut Row<T>) {
+    pub(crate) fn apply<S: RowStorage<T>>(self, row: &mut Row<T, S>) {
         match self {
             RowMod::SetCol(col_idx, new_val) => {
                 row.place(col_idx, new_val);
@@ -25,9 +102,57 @@ impl<T> RowMod<T> {
                 row.take(col_idx);
             }
             RowMod::UpdateCol(col_idx, f) => {
-                row.get_mut(col_idx).map(|val| {
-                    f(val);
-                });
+                if let Some(val) = row.get_mut(col_idx) {
+                    K::call_update(f, val);
+                }
+            }
+            RowMod::Patch(col_idx, patch) => {
+                if let Some(val) = row.get_mut(col_idx) {
+                    patch.apply(val);
+                }
+            }
+            RowMod::Resize(len) => {
+                row.resize(len);
+            }
+        }
+    }
+
+    /// Applies this modification and pushes the modifications that revert its changes on the elements to `undo_mods`, in the order they should be applied.
+    /// The old value is cloned before an update or a patch is applied. The length of the row is not restored, see `undo_row_mods`.
+    pub(crate) fn apply_with_undo<S: RowStorage<T>>(
+        self,
+        row: &mut Row<T, S>,
+        undo_mods: &mut Vec<Self>,
+    ) where
+        T: Clone,
+    {
+        match self {
+            RowMod::SetCol(col_idx, new_val) => undo_mods.push(match row.place(col_idx, new_val) {
+                Some(old_val) => RowMod::SetCol(col_idx, old_val),
+                None => RowMod::UnsetCol(col_idx),
+            }),
+            RowMod::UnsetCol(col_idx) => undo_mods.extend(
+                row.take(col_idx)
+                    .map(|old_val| RowMod::SetCol(col_idx, old_val)),
+            ),
+            RowMod::UpdateCol(col_idx, _) | RowMod::Patch(col_idx, _) => {
+                if let Some(old_val) = row.get(col_idx).cloned() {
+                    self.apply(row);
+                    undo_mods.push(RowMod::SetCol(col_idx, old_val));
+                }
+            }
+            RowMod::Resize(len) => {
+                // Only the present elements are visited, and the ones past `len` are moved out before the row shrinks.
+                let dropped: Vec<_> = row
+                    .iter()
+                    .map(|(col_idx, _)| col_idx)
+                    .filter(|col_idx| *col_idx >= len)
+                    .collect();
+                undo_mods.extend(dropped.into_iter().filter_map(|col_idx| {
+                    row.take(col_idx)
+                        .map(|old_val| RowMod::SetCol(col_idx, old_val))
+                }));
+                row.resize(len);
             }
         }
     }
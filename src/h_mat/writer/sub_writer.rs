@@ -1,31 +1,35 @@
 use std::marker::PhantomData;
 
-use crate::HMatWriter;
+use crate::{HMatWriter, WriterKind};
 
 /// Internal type used for the recursive implementations of the `GetSubWriter` trait.
 pub struct GetSubWriterDirective<T>(PhantomData<*const T>);
 
 /// Represents a writer type that can return one of its subwriters, e.g., `HMatWriter<T1, HMatWriter<T2, R>>` has a subwriter `HMatWriter<T2, R>`.
-pub trait GetSubWriter<T, Directive> {
+pub trait GetSubWriter<T, Directive, K: WriterKind<T>> {
     type Rem;
-    /// Returns the subwriter `HMatWriter<T, R>` as a mutable reference.
-    fn sub_writer_mut(&mut self) -> &mut HMatWriter<T, Self::Rem>;
+    /// The position of the subwriter in this writer, starting from `0` at the head.
+    const ROW_IDX: usize;
+    /// Returns the subwriter `HMatWriter<T, R, K>` as a mutable reference.
+    fn sub_writer_mut(&mut self) -> &mut HMatWriter<T, Self::Rem, K>;
 }
 
-impl<D, R> GetSubWriter<D, ()> for HMatWriter<D, R> {
+impl<D, R, K: WriterKind<D>> GetSubWriter<D, (), K> for HMatWriter<D, R, K> {
     type Rem = R;
-    fn sub_writer_mut(&mut self) -> &mut HMatWriter<D, Self::Rem> {
+    const ROW_IDX: usize = 0;
+    fn sub_writer_mut(&mut self) -> &mut HMatWriter<D, Self::Rem, K> {
         self
     }
 }
 
-impl<D, R, T, InnerDirective> GetSubWriter<D, GetSubWriterDirective<InnerDirective>>
-    for HMatWriter<T, R>
+impl<D, R, T, K: WriterKind<D> + WriterKind<T>, InnerDirective>
+    GetSubWriter<D, GetSubWriterDirective<InnerDirective>, K> for HMatWriter<T, R, K>
 where
-    R: GetSubWriter<D, InnerDirective>,
+    R: GetSubWriter<D, InnerDirective, K>,
 {
-    type Rem = <R as GetSubWriter<D, InnerDirective>>::Rem;
-    fn sub_writer_mut(&mut self) -> &mut HMatWriter<D, Self::Rem> {
+    type Rem = <R as GetSubWriter<D, InnerDirective, K>>::Rem;
+    const ROW_IDX: usize = <R as GetSubWriter<D, InnerDirective, K>>::ROW_IDX + 1;
+    fn sub_writer_mut(&mut self) -> &mut HMatWriter<D, Self::Rem, K> {
         self.rem.sub_writer_mut()
     }
 }
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use crate::{
+    AccessRowMut, ApplyWriterDirective, HMat, HMatWriter, Row, RowMod, RowStorage, WriteOrder,
+    WriterKind,
+};
+
+/// Represents a type that can receive a writer `W` to modify itself, and return the writer that reverts the modifications.
+pub trait ApplyWriterWithUndo<W, Directive> {
+    /// Applies the given writer and returns its inverse, i.e., a writer that restores the previous state when applied.
+    fn apply_with_undo(&mut self, w: W) -> W;
+}
+
+/// Applies the given modifications on the row, and returns the modifications that revert them in the order they should be applied.
+/// The reverting modifications end with a `Resize` if the length of the row changed, e.g., when a column past its end was set.
+fn undo_row_mods<T: Clone, K: WriterKind<T>, S: RowStorage<T>>(
+    row_mods: Vec<RowMod<T, K>>,
+    order: WriteOrder,
+    row: &mut Row<T, S>,
+) -> Vec<RowMod<T, K>> {
+    let old_len = row.len();
+    let mut undo_mods = Vec::new();
+    for row_mod in order.arrange(row_mods) {
+        row_mod.apply_with_undo(row, &mut undo_mods);
+    }
+    undo_mods.reverse();
+    if row.len() != old_len {
+        undo_mods.push(RowMod::Resize(old_len));
+    }
+    undo_mods
+}
+
+impl<D1, D2, R, K: WriterKind<D1> + WriterKind<D2>, A, Awt, Hh, Hr, Hs>
+    ApplyWriterWithUndo<HMatWriter<D1, HMatWriter<D2, R, K>, K>, ApplyWriterDirective<A, Awt>>
+    for HMat<Hh, Hr, Hs>
+where
+    Self: AccessRowMut<D1, A>,
+    Self: ApplyWriterWithUndo<HMatWriter<D2, R, K>, Awt>,
+    D1: Clone,
+{
+    fn apply_with_undo(
+        &mut self,
+        w: HMatWriter<D1, HMatWriter<D2, R, K>, K>,
+    ) -> HMatWriter<D1, HMatWriter<D2, R, K>, K> {
+        HMatWriter {
+            row_mods: undo_row_mods(w.row_mods, w.order, self.get_row_mut()),
+            order: WriteOrder::Insertion,
+            policy: Default::default(),
+            rem: self.apply_with_undo(w.rem),
+            pd: PhantomData,
+        }
+    }
+}
+
+impl<D, K: WriterKind<D>, A, Hh, Hr, Hs>
+    ApplyWriterWithUndo<HMatWriter<D, (), K>, ApplyWriterDirective<A, ()>> for HMat<Hh, Hr, Hs>
+where
+    Self: AccessRowMut<D, A>,
+    D: Clone,
+{
+    fn apply_with_undo(&mut self, w: HMatWriter<D, (), K>) -> HMatWriter<D, (), K> {
+        HMatWriter {
+            row_mods: undo_row_mods(w.row_mods, w.order, self.get_row_mut()),
+            order: WriteOrder::Insertion,
+            policy: Default::default(),
+            rem: (),
+            pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn undo() {
+        let mut mat = HMat::<f32, ()>::new_with([Some(0.5), Some(1.5)]).extend_with::<i32, _>([
+            Some(1),
+            None,
+            Some(3),
+        ]);
+        let mut writer = mat.new_writer();
+        writer.set_col(0, 10);
+        writer.set_col(1, 20);
+        writer.update_col(2, |val: &mut i32| *val *= 10);
+        writer.update_col(5, |val: &mut i32| *val *= 10);
+        writer.unset_col::<i32, _>(2);
+        writer.unset_col::<f32, _>(0);
+        writer.set_col(1, 2.5);
+        let undo_writer = mat.apply_with_undo(writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(10), Some(20), None])
+        );
+        // Undoing restores the previous state, and its inverse redoes the modifications.
+        let redo_writer = mat.apply_with_undo(undo_writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(1), None, Some(3)])
+        );
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(0.5), Some(1.5)])
+        );
+        mat.apply(redo_writer);
+        assert_eq!(
+            mat.get_row_ref::<i32, _>(),
+            &Row::from_iter([Some(10), Some(20), None])
+        );
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([None, Some(2.5)])
+        );
+    }
+
+    #[test]
+    fn undo_len() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Tag;
+        let mut mat = HMat::<f32, ()>::new_with([Some(0.5), None])
+            .extend_with_storage::<i32, SparseStorage<i32>>()
+            .extend_with_storage::<Tag, TagStorage<Tag>>();
+        mat.get_row_mut::<i32, _>().place(0, 1);
+        let orig = mat.clone();
+        // Every row grows past its end, and the f32 one is shrunk back in the writer.
+        let mut writer = mat.new_writer_ordered();
+        writer.set_col(3, 0.5);
+        writer.unset_col::<f32, _>(3);
+        writer.set_col(5, 2);
+        writer.update_col(5, |val: &mut i32| *val += 1);
+        writer.set_col(4, Tag);
+        let undo_writer = mat.apply_with_undo(writer);
+        assert_eq!(mat.num_cols(), 6);
+        let redo_writer = mat.apply_with_undo(undo_writer);
+        assert_eq!(mat.num_cols(), orig.num_cols());
+        assert_eq!(mat.get_row_ref::<f32, _>(), orig.get_row_ref::<f32, _>());
+        assert_eq!(mat.get_row_ref::<i32, _>(), orig.get_row_ref::<i32, _>());
+        assert_eq!(mat.get_row_ref::<Tag, _>(), orig.get_row_ref::<Tag, _>());
+        // Redoing grows the rows again, including the trailing empty element of the f32 row.
+        mat.apply(redo_writer);
+        assert_eq!(
+            mat.get_row_ref::<f32, _>(),
+            &Row::from_iter([Some(0.5), None, None, None])
+        );
+        assert_eq!(mat.get_row_ref::<i32, _>().get(5), Some(&3));
+        assert_eq!(mat.get_row_ref::<Tag, _>().len(), 5);
+    }
+}